@@ -1,5 +1,5 @@
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::decomposition::Counter;
+use biodivine_lib_param_bn::decomposition::SccClassification;
 use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
 use biodivine_lib_param_bn::BooleanNetwork;
 use std::convert::TryFrom;
@@ -23,7 +23,7 @@ fn main() {
 }
 
 fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
-    let mut counter = Counter::new(graph);
+    let mut counter = SccClassification::new(graph);
     let magnitude = graph
         .unit_colored_vertices()
         .vertices()
@@ -50,7 +50,7 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
         println!(
             "Universes: {}; SCCs: {}; Remaining: {}/{}",
             universes.len(),
-            counter.len(),
+            counter.max_components(),
             remaining + universe.approx_cardinality(),
             graph.unit_colored_vertices().approx_cardinality()
         );
@@ -90,7 +90,7 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
             if scc.vertices().approx_cardinality() < cut_off {
                 println!("TOO SMALL.")
             } else {
-                counter.push(&non_trivial_colors);
+                counter.push(&scc.intersect_colors(&non_trivial_colors));
             }
         } else {
             println!("TRIVIAL.");
@@ -120,8 +120,8 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
         }
     }
 
-    counter.print();
-    counter.len()
+    print!("{}", counter);
+    counter.max_components()
 }
 
 fn fwd_normal(
@@ -1,5 +1,6 @@
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::decomposition::Counter;
+use biodivine_lib_param_bn::decomposition::SccClassification;
+use biodivine_lib_param_bn::reachability::{reach_bwd, reach_fwd};
 use biodivine_lib_param_bn::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
 use biodivine_lib_param_bn::BooleanNetwork;
 use std::convert::TryFrom;
@@ -23,7 +24,7 @@ fn main() {
 }
 
 fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
-    let mut counter = Counter::new(graph);
+    let mut counter = SccClassification::new(graph);
 
     let mut universes = vec![(
         graph.mk_unit_colored_vertices(),
@@ -44,7 +45,7 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
         println!(
             "Universes: {}; SCCs: {}; Remaining: {}/{}",
             universes.len(),
-            counter.len(),
+            counter.max_components(),
             remaining + universe.approx_cardinality(),
             graph.unit_colored_vertices().approx_cardinality()
         );
@@ -69,8 +70,8 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
 
         let pivot = &universe.pick_vertex();
         let start_reach = std::time::SystemTime::now();
-        let fwd = fwd_saturation(graph, &universe, pivot.clone());
-        let bwd = bwd_saturation(graph, &universe, pivot.clone());
+        let fwd = reach_fwd(graph, pivot, universe);
+        let bwd = reach_bwd(graph, pivot, universe);
         reach += start_reach.elapsed().unwrap().as_millis();
 
         let scc = &fwd.intersect(&bwd);
@@ -83,7 +84,7 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
         );
         if !non_trivial_colors.is_empty() {
             if scc.vertices().approx_cardinality() > too_small {
-                counter.push(&non_trivial_colors);
+                counter.push(&scc.intersect_colors(&non_trivial_colors));
             }
         } else {
             println!("TRIVIAL.");
@@ -113,60 +114,8 @@ fn decomposition(graph: &SymbolicAsyncGraph) -> usize {
         }
     }
 
-    counter.print();
-    counter.len()
-}
-
-fn fwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut fwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'fwd: loop {
-        if fwd.as_bdd().size() > 100_000 {
-            println!(
-                "FWD: {}; {}/{}",
-                fwd.as_bdd().size(),
-                fwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let successors = graph.var_post(var, &fwd).intersect(universe);
-
-            if !successors.is_subset(&fwd) {
-                fwd = fwd.union(&successors);
-                continue 'fwd;
-            }
-        }
-        return fwd;
-    }
-}
-
-fn bwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut bwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'bwd: loop {
-        if bwd.as_bdd().size() > 100_000 {
-            println!(
-                "BWD: {}; {}/{}",
-                bwd.as_bdd().size(),
-                bwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let predecessors = graph.var_pre(var, &bwd).intersect(universe);
-
-            if !predecessors.is_subset(&bwd) {
-                bwd = bwd.union(&predecessors);
-                continue 'bwd;
-            }
-        }
-        return bwd;
-    }
+    print!("{}", counter);
+    counter.max_components()
 }
 
 fn trim(graph: &SymbolicAsyncGraph, mut set: GraphColoredVertices) -> GraphColoredVertices {
@@ -1,9 +1,10 @@
 use biodivine_lib_param_bn::symbolic_async_graph::{SymbolicAsyncGraph, GraphColoredVertices};
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::reachability::{reach_bwd, reach_fwd};
 use rayon::prelude::*;
 use std::sync::Mutex;
 use std::time::{SystemTime, Duration};
-use biodivine_lib_param_bn::decomposition::Counter;
+use biodivine_lib_param_bn::decomposition::SccClassification;
 use std::cmp::min;
 use std::io::Read;
 use biodivine_lib_param_bn::BooleanNetwork;
@@ -18,7 +19,6 @@ fn main() {
     let mut buffer = String::new();
     std::io::stdin().read_to_string(&mut buffer).unwrap();
 
-
     let model = BooleanNetwork::try_from(buffer.as_str()).unwrap();
     let model = shuffle(model);
     println!("Model vars: {}", model.as_graph().num_vars());
@@ -26,7 +26,6 @@ fn main() {
         println!("{:?}: {}", var, model.get_variable_name(var));
     }
 
-
     let graph = SymbolicAsyncGraph::new(model).unwrap();
     println!(
         "Model params: {}",
@@ -45,7 +44,6 @@ fn main() {
     println!("Counted: {}", count);
 }
 
-
 fn shuffle(mut network: BooleanNetwork) -> BooleanNetwork {
     let mut new_index = (0..network.num_vars()).collect::<Vec<_>>();
     new_index.shuffle(&mut rand::thread_rng());
@@ -59,7 +57,7 @@ fn shuffle(mut network: BooleanNetwork) -> BooleanNetwork {
 }
 
 fn sccs(graph: &SymbolicAsyncGraph, universes: Vec<GraphColoredVertices>, threads: u32) -> usize {
-    let aggregated = Mutex::new(Counter::new(graph));
+    let aggregated = Mutex::new(SccClassification::new(graph));
     let queue = ParQueue::new(threads, universes);
     let total_size = graph.unit_colored_vertices().approx_cardinality();
     let remaining = Mutex::new(graph.unit_colored_vertices().approx_cardinality());
@@ -68,7 +66,7 @@ fn sccs(graph: &SymbolicAsyncGraph, universes: Vec<GraphColoredVertices>, thread
     rayon::scope(|s| {
         for _ in 0..threads {
             s.spawn(|_| {
-                let mut counter = Counter::new(graph);
+                let mut counter = SccClassification::new(graph);
                 while let Some((universe, should_trim)) = queue.pop() {
                     let removed =
                         one_scc(graph, &queue, &mut counter, universe, should_trim);
@@ -90,7 +88,7 @@ fn sccs(graph: &SymbolicAsyncGraph, universes: Vec<GraphColoredVertices>, thread
                                 remaining_seconds.round(),
                                 unit_per_second.round(),
                                 removed,
-                                counter.len(),
+                                counter.max_components(),
                             );
                         }
                     }
@@ -101,14 +99,14 @@ fn sccs(graph: &SymbolicAsyncGraph, universes: Vec<GraphColoredVertices>, thread
     });
 
     let aggregated = aggregated.into_inner().unwrap();
-    aggregated.print();
-    aggregated.len()
+    print!("{}", aggregated);
+    aggregated.max_components()
 }
 
 fn one_scc(
     graph: &SymbolicAsyncGraph,
     queue: &ParQueue,
-    counter: &mut Counter,
+    counter: &mut SccClassification,
     universe: GraphColoredVertices,
     should_trim: bool,
 ) -> f64 {
@@ -124,13 +122,13 @@ fn one_scc(
     }
 
     let pivot = trimmed.pick_vertex();
-    let fwd = fwd_saturation(graph, &trimmed, pivot.clone());
-    let bwd = bwd_saturation(graph, &trimmed, pivot.clone());
+    let fwd = reach_fwd(graph, &pivot, &trimmed);
+    let bwd = reach_bwd(graph, &pivot, &trimmed);
 
     let scc = fwd.intersect(&bwd);
     let non_trivial_scc = scc.minus(&pivot).colors();
     if !non_trivial_scc.is_empty() {
-        counter.push(&non_trivial_scc);
+        counter.push(&scc.intersect_colors(&non_trivial_scc));
     }
 
     let fwd_only = fwd.minus(&bwd);
@@ -251,8 +249,8 @@ fn split_by(
     universe: &GraphColoredVertices,
     pivots: &GraphColoredVertices
 ) {
-    let fwd = fwd_saturation(graph, universe, pivots.clone());
-    let bwd = bwd_saturation(graph, universe, pivots.clone());
+    let fwd = reach_fwd(graph, pivots, universe);
+    let bwd = reach_bwd(graph, pivots, universe);
 
     let both = fwd.intersect(&bwd);
     if !both.is_empty() { results.push(both); }
@@ -264,60 +262,6 @@ fn split_by(
     if !bwd_only.is_empty() { results.push(bwd_only); }
 }
 
-/// Normal saturated forward reachability inside the given `universe`.
-fn fwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut fwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'fwd: loop {
-        if fwd.as_bdd().size() > 100_000 {
-            println!(
-                "FWD: {}; {}/{}",
-                fwd.as_bdd().size(),
-                fwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let successors = graph.var_post(var, &fwd).intersect(universe);
-
-            if !successors.is_subset(&fwd) {
-                fwd = fwd.union(&successors);
-                continue 'fwd;
-            }
-        }
-        return fwd;
-    }
-}
-
-// Normal saturated backwards reachability inside given `universe`.
-fn bwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut bwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'bwd: loop {
-        if bwd.as_bdd().size() > 100_000 {
-            println!(
-                "BWD: {}; {}/{}",
-                bwd.as_bdd().size(),
-                bwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let predecessors = graph.var_pre(var, &bwd).intersect(universe);
-
-            if !predecessors.is_subset(&bwd) {
-                bwd = bwd.union(&predecessors);
-                continue 'bwd;
-            }
-        }
-        return bwd;
-    }
-}
-
 /// Iteratively remove vertices with no outgoing/incoming edges.
 /// It is only allowed when the symbolic size of the set is less than 10k nodes.
 fn trim(graph: &SymbolicAsyncGraph, mut set: GraphColoredVertices) -> GraphColoredVertices {
@@ -365,7 +309,6 @@ fn trim(graph: &SymbolicAsyncGraph, mut set: GraphColoredVertices) -> GraphColor
     set
 }
 
-
 struct ParQueue {
     threads: u32,
     data: Mutex<(Vec<GraphColoredVertices>, u32)>,
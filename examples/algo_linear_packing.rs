@@ -6,7 +6,7 @@ use std::io::Read;
 use std::time::Instant;
 
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
-use biodivine_lib_param_bn::decomposition::Counter;
+use biodivine_lib_param_bn::decomposition::SccClassification;
 use biodivine_lib_param_bn::stats::*;
 use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
 use biodivine_lib_param_bn::BooleanNetwork;
@@ -73,7 +73,7 @@ fn push_args(
 }
 
 fn find_sccs(graph: &StatSymbolicAsyncGraph) -> usize {
-    let mut counter = Counter::new(graph);
+    let mut counter = SccClassification::new(graph);
     let vertices = graph.mk_unit_colored_vertices();
 
     let spine_set = ColoredSpineSet {
@@ -93,7 +93,7 @@ fn find_sccs(graph: &StatSymbolicAsyncGraph) -> usize {
             println!(
                 "Universes: {}; SCCs: {}; Remaining: {}/{}",
                 universes.len(),
-                counter.len(),
+                counter.max_components(),
                 remaining + universe.approx_cardinality(),
                 graph.unit_colored_vertices().approx_cardinality()
             );
@@ -161,7 +161,7 @@ fn find_sccs(graph: &StatSymbolicAsyncGraph) -> usize {
             sccs.vertices().approx_cardinality()
         );
         if !non_trivial_colors.is_empty() {
-            counter.push(&non_trivial_colors);
+            counter.push(&sccs.intersect_colors(&non_trivial_colors));
         } else {
             #[cfg(feature = "logging")]
             println!("TRIVIAL.");
@@ -199,8 +199,8 @@ fn find_sccs(graph: &StatSymbolicAsyncGraph) -> usize {
     }
 
     #[cfg(feature = "logging")]
-    counter.print();
-    counter.len()
+    print!("{}", counter);
+    counter.max_components()
 }
 
 fn skel_forward(
@@ -3,8 +3,9 @@ use std::io::Read;
 use biodivine_lib_param_bn::BooleanNetwork;
 use std::convert::TryFrom;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::reachability::{reach_bwd, reach_fwd};
 use rayon::prelude::*;
-use biodivine_lib_param_bn::decomposition::Counter;
+use biodivine_lib_param_bn::decomposition::SccClassification;
 
 fn main() {
     let mut buffer = String::new();
@@ -32,8 +33,8 @@ fn main() {
         {
             let mapped: Vec<Vec<GraphColoredVertices>> = universes.par_iter().map(|universe| {
                 //println!("Process {} / {}", universe.approx_cardinality(), universe.as_bdd().size());
-                let fwd = fwd_saturation(&graph, &universe, is_true.intersect(&universe));
-                let bwd = bwd_saturation(&graph, &universe, is_true.intersect(&universe));
+                let fwd = reach_fwd(&graph, &is_true.intersect(universe), universe);
+                let bwd = reach_bwd(&graph, &is_true.intersect(universe), universe);
 
                 let mut result = Vec::new();
                 let rest = universe.minus(&fwd).minus(&bwd);
@@ -64,8 +65,8 @@ fn main() {
         {
             let mapped: Vec<Vec<GraphColoredVertices>> = universes.par_iter().map(|universe| {
                 //println!("Process {} / {}", universe.approx_cardinality(), universe.as_bdd().size());
-                let fwd = fwd_saturation(&graph, &universe, is_false.intersect(&universe));
-                let bwd = bwd_saturation(&graph, &universe, is_false.intersect(&universe));
+                let fwd = reach_fwd(&graph, &is_false.intersect(universe), universe);
+                let bwd = reach_bwd(&graph, &is_false.intersect(universe), universe);
 
                 let mut result = Vec::new();
                 let rest = universe.minus(&fwd).minus(&bwd);
@@ -118,9 +119,8 @@ fn main() {
     println!("Counted: {}", count);
 }
 
-
 fn decomposition(graph: &SymbolicAsyncGraph, mut universes: Vec<GraphColoredVertices>) -> usize {
-    let mut counter = Counter::new(graph);
+    let mut counter = SccClassification::new(graph);
 
     let start = std::time::SystemTime::now();
     let mut trimming = 0;
@@ -136,7 +136,7 @@ fn decomposition(graph: &SymbolicAsyncGraph, mut universes: Vec<GraphColoredVert
         println!(
             "Universes: {}; SCCs: {};",
             universes.len(),
-            counter.len(),
+            counter.max_components(),
         );
         println!(
             "Elapsed: {}; Trim: {}; Reach: {};",
@@ -159,8 +159,8 @@ fn decomposition(graph: &SymbolicAsyncGraph, mut universes: Vec<GraphColoredVert
 
         let pivot = &universe.pick_vertex();
         let start_reach = std::time::SystemTime::now();
-        let fwd = fwd_saturation(graph, &universe, pivot.clone());
-        let bwd = bwd_saturation(graph, &universe, pivot.clone());
+        let fwd = reach_fwd(graph, pivot, universe);
+        let bwd = reach_bwd(graph, pivot, universe);
         reach += start_reach.elapsed().unwrap().as_millis();
 
         let scc = &fwd.intersect(&bwd);
@@ -173,7 +173,7 @@ fn decomposition(graph: &SymbolicAsyncGraph, mut universes: Vec<GraphColoredVert
         );
         if !non_trivial_colors.is_empty() {
             //if scc.vertices().approx_cardinality() > too_small {
-                counter.push(&non_trivial_colors);
+                counter.push(&scc.intersect_colors(&non_trivial_colors));
             //}
         } else {
             println!("TRIVIAL.");
@@ -203,64 +203,10 @@ fn decomposition(graph: &SymbolicAsyncGraph, mut universes: Vec<GraphColoredVert
         }
     }
 
-    counter.print();
-    counter.len()
-}
-
-
-fn fwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut fwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'fwd: loop {
-        if fwd.as_bdd().size() > 100_000 {
-            println!(
-                "FWD: {}; {}/{}",
-                fwd.as_bdd().size(),
-                fwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let successors = graph.var_post(var, &fwd).intersect(universe);
-
-            if !successors.is_subset(&fwd) {
-                fwd = fwd.union(&successors);
-                continue 'fwd;
-            }
-        }
-        return fwd;
-    }
+    print!("{}", counter);
+    counter.max_components()
 }
 
-fn bwd_saturation(
-    graph: &SymbolicAsyncGraph,
-    universe: &GraphColoredVertices,
-    mut bwd: GraphColoredVertices,
-) -> GraphColoredVertices {
-    'bwd: loop {
-        if bwd.as_bdd().size() > 100_000 {
-            println!(
-                "BWD: {}; {}/{}",
-                bwd.as_bdd().size(),
-                bwd.approx_cardinality(),
-                universe.approx_cardinality()
-            );
-        }
-        for var in graph.as_network().variables().rev() {
-            let predecessors = graph.var_pre(var, &bwd).intersect(universe);
-
-            if !predecessors.is_subset(&bwd) {
-                bwd = bwd.union(&predecessors);
-                continue 'bwd;
-            }
-        }
-        return bwd;
-    }
-}
-
-
 fn trim(graph: &SymbolicAsyncGraph, mut set: GraphColoredVertices) -> GraphColoredVertices {
     let initial = set.as_bdd().size();
     //println!("Start trim: {}", initial);
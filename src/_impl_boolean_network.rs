@@ -131,6 +131,69 @@ impl BooleanNetwork {
     }
 }
 
+/// Perturbations of a `BooleanNetwork`.
+impl BooleanNetwork {
+    /// Create a copy of this network where the given variables are fixed to constants, e.g.
+    /// to represent a knockout (`false`) or an over-expression (`true`) mutant.
+    ///
+    /// The update function of each fixed variable is replaced by the constant and its
+    /// regulations are removed. Parameters which are no longer used are removed as well.
+    pub fn fix_variables(&self, values: &[(VariableId, bool)]) -> BooleanNetwork {
+        let fixed_value = |var: VariableId| -> Option<bool> {
+            values
+                .iter()
+                .find(|(v, _)| *v == var)
+                .map(|(_, value)| *value)
+        };
+        let mut graph = self.graph.clone();
+        graph
+            .regulations
+            .retain(|regulation| fixed_value(regulation.target).is_none());
+        let update_functions: Vec<Option<FnUpdate>> = self
+            .variables()
+            .map(|var| match fixed_value(var) {
+                Some(value) => Some(FnUpdate::Const(value)),
+                None => self.update_functions[var.0].clone(),
+            })
+            .collect();
+
+        // Remove unused parameters and re-index the remaining ones.
+        let mut used = vec![false; self.num_parameters()];
+        for function in update_functions.iter().flatten() {
+            for parameter in function.collect_parameters() {
+                used[parameter.0] = true;
+            }
+        }
+        let mut network = BooleanNetwork::new(graph);
+        let mut mapping = vec![None; self.num_parameters()];
+        for parameter in self.parameters() {
+            if used[parameter.0] {
+                let Parameter { name, arity } = &self[parameter];
+                mapping[parameter.0] = Some(network.add_parameter(name, *arity).unwrap());
+            }
+        }
+        network.update_functions = update_functions
+            .into_iter()
+            .map(|function| function.map(|it| rename_parameters(&it, &mapping)))
+            .collect();
+        network
+    }
+}
+
+/// **(internal)** Replace every parameter of the `function` using the given `mapping`.
+fn rename_parameters(function: &FnUpdate, mapping: &[Option<ParameterId>]) -> FnUpdate {
+    match function {
+        FnUpdate::Const(_) | FnUpdate::Var(_) => function.clone(),
+        FnUpdate::Param(id, args) => FnUpdate::Param(mapping[id.0].unwrap(), args.clone()),
+        FnUpdate::Not(inner) => FnUpdate::Not(Box::new(rename_parameters(inner, mapping))),
+        FnUpdate::Binary(op, left, right) => FnUpdate::Binary(
+            *op,
+            Box::new(rename_parameters(left, mapping)),
+            Box::new(rename_parameters(right, mapping)),
+        ),
+    }
+}
+
 /// Some utility methods for accessing the structure of a `BooleanNetwork`. Some of them are just
 /// delegating to the internal `RegulatoryGraph`, but we have a copy here as well because they
 /// are used very often.
@@ -0,0 +1,74 @@
+use crate::{
+    ConstraintViolation, Monotonicity, Regulation, RegulationConstraint, RegulatoryGraph,
+    StaticConstraintError, VariableId,
+};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Basic getters.
+impl ConstraintViolation {
+    /// The regulation whose constraint is violated.
+    pub fn get_regulation(&self) -> &Regulation {
+        &self.regulation
+    }
+
+    /// The violated constraint of the regulation.
+    pub fn get_constraint(&self) -> RegulationConstraint {
+        self.constraint
+    }
+
+    /// A valuation of the other regulators of the target which witnesses the violation.
+    pub fn get_counterexample(&self) -> &[(VariableId, bool)] {
+        &self.counterexample
+    }
+
+    /// Convert to a human-readable string using variable names provided by
+    /// a `RegulatoryGraph`.
+    pub fn to_string(&self, context: &RegulatoryGraph) -> String {
+        let regulator = context.get_variable_name(self.regulation.regulator);
+        let target = context.get_variable_name(self.regulation.target);
+        let problem = match self.constraint {
+            RegulationConstraint::Observability => {
+                format!("{} has no effect in {}", regulator, target)
+            }
+            RegulationConstraint::Monotonicity(monotonicity) => {
+                let monotonicity = match monotonicity {
+                    Monotonicity::Activation => "activating",
+                    Monotonicity::Inhibition => "inhibiting",
+                };
+                format!("{} not {} in {}", regulator, monotonicity, target)
+            }
+        };
+        if self.counterexample.is_empty() {
+            format!("{}.", problem)
+        } else {
+            let input: Vec<String> = self
+                .counterexample
+                .iter()
+                .map(|(var, value)| {
+                    format!("{} = {}", context.get_variable_name(*var), *value as u8)
+                })
+                .collect();
+            format!("{} (e.g. for {}).", problem, input.join(", "))
+        }
+    }
+}
+
+impl StaticConstraintError {
+    /// All violated constraints, in the order of the regulations in the network.
+    pub fn get_violations(&self) -> &[ConstraintViolation] {
+        &self.violations
+    }
+}
+
+impl Display for StaticConstraintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "No update functions satisfy given constraints: ")?;
+        for violation in &self.violations {
+            writeln!(f, " - {}", violation.to_string(&self.graph))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for StaticConstraintError {}
@@ -0,0 +1,36 @@
+use crate::BooleanNetwork;
+use std::convert::TryFrom;
+
+/// The parametrised network used throughout the tutorial, with an explicit parameter `f`
+/// and an implicit (non-observable) regulation of `B`.
+pub(crate) fn tutorial_network() -> BooleanNetwork {
+    BooleanNetwork::try_from(
+        r"
+        A -> B
+        C -|? B
+        $B: A
+        C -> A
+        B -> A
+        A -| A
+        $A: C | f(A, B)
+    ",
+    )
+    .unwrap()
+}
+
+/// A network whose update function of `t` violates the static constraints of two of its
+/// regulations: `a` is not activating and `b` has no effect in `t`.
+pub(crate) fn constraint_violating_network() -> BooleanNetwork {
+    BooleanNetwork::try_from(
+        r"
+        a -> t
+        b -| t
+        c -| t
+        $a: true
+        $b: true
+        $c: true
+        $t: !a & !c
+    ",
+    )
+    .unwrap()
+}
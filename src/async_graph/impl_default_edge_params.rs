@@ -1,11 +1,13 @@
 use crate::async_graph::{AsyncGraphEdgeParams, DefaultEdgeParams};
 use crate::bdd_params::{build_static_constraints, BddParameterEncoder, BddParams};
 use crate::biodivine_std::structs::IdState;
-use crate::biodivine_std::traits::Set;
 use crate::{BooleanNetwork, VariableId};
 
 impl DefaultEdgeParams {
     /// New default edge parametrisation for the given network. Warning: computes the unit set, which can be expensive.
+    ///
+    /// The error explains the violated static constraints of the network regulations. Use
+    /// `bdd_params::build_static_constraints` to obtain them as a `StaticConstraintError`.
     pub fn new(network: BooleanNetwork) -> Result<DefaultEdgeParams, String> {
         let encoder = BddParameterEncoder::new(&network);
         Self::new_with_custom_encoder(network, encoder)
@@ -15,17 +17,14 @@ impl DefaultEdgeParams {
         network: BooleanNetwork,
         encoder: BddParameterEncoder,
     ) -> Result<DefaultEdgeParams, String> {
-        let unit_set = BddParams::from(build_static_constraints(&network, &encoder));
-        if unit_set.is_empty() {
-            Err("No update functions satisfy given constraints".to_string())
-        } else {
-            Ok(DefaultEdgeParams {
-                empty_set: BddParams::from(encoder.bdd_variables.mk_false()),
-                network,
-                encoder,
-                unit_set,
-            })
-        }
+        let unit_set =
+            build_static_constraints(&network, &encoder).map_err(|error| error.to_string())?;
+        Ok(DefaultEdgeParams {
+            empty_set: BddParams::from(encoder.bdd_variables.mk_false()),
+            unit_set: BddParams::from(unit_set),
+            network,
+            encoder,
+        })
     }
 }
 
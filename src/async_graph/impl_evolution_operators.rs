@@ -161,6 +161,10 @@ mod tests {
         .collect();
 
         assert_eq!(9.0, graph.unit_params().cardinality());
+        assert_eq!(
+            num_bigint::BigInt::from(9),
+            graph.unit_params().exact_cardinality()
+        );
 
         let mut fwd_edges: HashSet<(IdState, IdState, BddParams)> = HashSet::new();
         let mut bwd_edges: HashSet<(IdState, IdState, BddParams)> = HashSet::new();
@@ -1,6 +1,8 @@
 use super::BddParams;
 use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::_impl_exact_cardinality::exact_bdd_cardinality;
 use biodivine_lib_bdd::Bdd;
+use num_bigint::BigInt;
 
 impl BddParams {
     /// Consume these `BddParams` and turn them into a raw `Bdd`.
@@ -15,6 +17,11 @@ impl BddParams {
     pub fn cardinality(&self) -> f64 {
         self.0.cardinality()
     }
+
+    /// Exact number of parametrisations in this set.
+    pub fn exact_cardinality(&self) -> BigInt {
+        exact_bdd_cardinality(&self.0)
+    }
 }
 
 impl Set for BddParams {
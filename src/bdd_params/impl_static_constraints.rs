@@ -3,69 +3,107 @@
 
 use crate::bdd_params::BddParameterEncoder;
 use crate::biodivine_std::structs::IdState;
-use crate::{BooleanNetwork, FnUpdate, Monotonicity, Regulation, VariableId};
+use crate::{
+    BooleanNetwork, ConstraintViolation, FnUpdate, Monotonicity, Regulation, RegulationConstraint,
+    StaticConstraintError, VariableId,
+};
 use biodivine_lib_bdd::{bdd, Bdd};
 use std::ops::Range;
 
 /// Build a `Bdd` which describes all valuations satisfying the static constraints
 /// of the given `BooleanNetwork`.
-pub fn build_static_constraints(bn: &BooleanNetwork, encoder: &BddParameterEncoder) -> Bdd {
+///
+/// Constraints which cannot be satisfied together with the constraints applied before them
+/// are skipped and reported in the resulting error.
+pub fn build_static_constraints(
+    bn: &BooleanNetwork,
+    encoder: &BddParameterEncoder,
+) -> Result<Bdd, StaticConstraintError> {
     let mut condition = encoder.bdd_variables.mk_true();
+    let mut violations = Vec::new();
     let ctx = Ctx::new(bn, encoder);
     for r in &bn.graph.regulations {
-        if let Some(fun) = bn.get_update_function(r.target) {
-            if r.monotonicity != None {
-                let monotonicity = build_monotonicity_explicit(&ctx, r, fun);
-                condition = bdd!(condition & monotonicity);
-                if condition.is_false() {
-                    println!(
-                        "Regulation {} -> {} is not monotonous.",
-                        bn.graph.get_variable(r.regulator).name,
-                        bn.graph.get_variable(r.target).name
-                    );
-                    break;
-                }
-            }
-            if r.observable {
-                let observability = build_observability_explicit(&ctx, r, fun);
-                condition = bdd!(condition & observability);
-                if condition.is_false() {
-                    println!(
-                        "Regulation {} -> {} is not observable.",
-                        bn.graph.get_variable(r.regulator).name,
-                        bn.graph.get_variable(r.target).name
-                    );
-                    break;
-                }
-            }
-        } else {
-            if r.monotonicity != None {
-                let monotonicity = build_monotonicity_implicit(&ctx, r);
-                condition = bdd!(condition & monotonicity);
-                if condition.is_false() {
-                    println!(
-                        "Regulation {} -> {} is not monotonous.",
-                        bn.graph.get_variable(r.regulator).name,
-                        bn.graph.get_variable(r.target).name
-                    );
-                    break;
-                }
+        let fun = bn.get_update_function(r.target);
+        if let Some(monotonicity) = r.monotonicity {
+            let constraint = match fun {
+                Some(fun) => build_monotonicity_explicit(&ctx, r, fun),
+                None => build_monotonicity_implicit(&ctx, r),
+            };
+            let restricted = bdd!(condition & constraint);
+            if restricted.is_false() {
+                violations.push(ConstraintViolation {
+                    regulation: r.clone(),
+                    constraint: RegulationConstraint::Monotonicity(monotonicity),
+                    counterexample: find_non_monotonous_input(&ctx, r, &condition),
+                });
+            } else {
+                condition = restricted;
             }
-            if r.observable {
-                let observability = build_observability_implicit(&ctx, r);
-                condition = bdd!(condition & observability);
-                if condition.is_false() {
-                    println!(
-                        "Regulation {} -> {} is not observable.",
-                        bn.graph.get_variable(r.regulator).name,
-                        bn.graph.get_variable(r.target).name
-                    );
-                    break;
-                }
+        }
+        if r.observable {
+            let constraint = match fun {
+                Some(fun) => build_observability_explicit(&ctx, r, fun),
+                None => build_observability_implicit(&ctx, r),
+            };
+            let restricted = bdd!(condition & constraint);
+            if restricted.is_false() {
+                // No input is observable, so any input is a counterexample.
+                let input = InputStatesPairIterator::new(bn, r).next();
+                violations.push(ConstraintViolation {
+                    regulation: r.clone(),
+                    constraint: RegulationConstraint::Observability,
+                    counterexample: input
+                        .map(|(inactive, _)| input_valuation(bn, r, inactive))
+                        .unwrap_or_default(),
+                });
+            } else {
+                condition = restricted;
             }
         }
     }
-    condition
+    if violations.is_empty() {
+        Ok(condition)
+    } else {
+        Err(StaticConstraintError {
+            graph: bn.as_graph().clone(),
+            violations,
+        })
+    }
+}
+
+/// **(internal)** Find an input of the `regulation` target such that the pair of function
+/// entries for this input is not monotonous in any valuation of the `condition` which is
+/// monotonous in the previously examined inputs.
+fn find_non_monotonous_input<'a>(
+    ctx: &Ctx<'a>,
+    regulation: &'a Regulation,
+    condition: &Bdd,
+) -> Vec<(VariableId, bool)> {
+    let mut condition = condition.clone();
+    for states in InputStatesPairIterator::new(ctx.bn, regulation) {
+        let (inactive, active) = ctx.pair(states, regulation.target);
+        let monotonous =
+            build_monotonicity_pair(&inactive, &active, regulation.monotonicity.unwrap());
+        condition = bdd!(condition & monotonous);
+        if condition.is_false() {
+            return input_valuation(ctx.bn, regulation, states.0);
+        }
+    }
+    Vec::new()
+}
+
+/// **(internal)** The values of the `regulation` target regulators (except for the regulator
+/// itself) in the given `state`.
+fn input_valuation(
+    bn: &BooleanNetwork,
+    regulation: &Regulation,
+    state: IdState,
+) -> Vec<(VariableId, bool)> {
+    bn.regulators(regulation.target)
+        .into_iter()
+        .filter(|var| *var != regulation.regulator)
+        .map(|var| (var, state.get_bit(var.0)))
+        .collect()
 }
 
 struct Ctx<'a> {
@@ -86,6 +124,15 @@ impl<'a> Ctx<'a> {
         (inactive, active)
     }
 
+    /// Transform a table pair into a pair of `Bdd`s using the (explicit or implicit) update
+    /// function of the given `variable`.
+    pub fn pair(&self, states: (IdState, IdState), variable: VariableId) -> (Bdd, Bdd) {
+        match self.bn.get_update_function(variable) {
+            Some(fun) => self.pair_explicit(states, fun),
+            None => self.pair_implicit(states, variable),
+        }
+    }
+
     /// Transform a table pair into a pair of `Bdd`s assuming an implicit update function.
     pub fn pair_implicit(&self, states: (IdState, IdState), variable: VariableId) -> (Bdd, Bdd) {
         let (inactive, active) = states;
@@ -220,3 +267,33 @@ fn build_monotonicity_pair(inactive: &Bdd, active: &Bdd, monotonicity: Monotonic
         Monotonicity::Inhibition => bdd!(active => inactive),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::constraint_violating_network;
+    use crate::bdd_params::{build_static_constraints, BddParameterEncoder};
+    use crate::symbolic_async_graph::SymbolicContext;
+    use crate::{BooleanNetwork, ConstraintViolation, VariableId};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn static_constraint_violations() {
+        let bn = constraint_violating_network();
+        let encoder = BddParameterEncoder::new(&bn);
+        let error = build_static_constraints(&bn, &encoder).unwrap_err();
+        let context = SymbolicContext::new(&bn).unwrap();
+        let expected = context.mk_regulation_constraints(&bn).unwrap_err();
+
+        // The counterexamples can differ in the unconstrained inputs.
+        let summary = |it: &ConstraintViolation| (it.get_regulation().clone(), it.get_constraint());
+        let actual: Vec<_> = error.get_violations().iter().map(summary).collect();
+        let expected: Vec<_> = expected.get_violations().iter().map(summary).collect();
+        assert_eq!(expected, actual);
+        assert!(error.get_violations()[0]
+            .get_counterexample()
+            .contains(&(VariableId(2), false)));
+
+        let bn = BooleanNetwork::try_from("a -> t \n $a: true \n $t: a").unwrap();
+        assert!(build_static_constraints(&bn, &BddParameterEncoder::new(&bn)).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! Cooperative interruption of long running symbolic fixed-point computations.
+//!
+//! A `Budget` combines an optional `CancellationToken` with an optional wall-clock
+//! deadline and an optional limit on the size of the BDDs created during the computation.
+//! Interruptible algorithms check the budget after every fixed-point step and when it is
+//! exhausted, they stop and return an `Interrupted` error carrying the partial result
+//! computed so far (the exact meaning of the partial result is documented by each algorithm).
+//!
+//! Note that the checks are cooperative: a single symbolic operation (e.g. one `post`) is
+//! never interrupted, so the computation can overshoot the deadline or the BDD size limit
+//! by one step.
+
+use biodivine_lib_bdd::Bdd;
+use std::fmt::{Display, Error, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A shared flag which can be used to cancel a running computation from another thread.
+///
+/// Clones of the token share the same flag, so cancelling one clone cancels all of them.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+/// Limits on the amount of work an interruptible symbolic computation can perform.
+///
+/// The `Default` budget is unlimited.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    max_bdd_size: Option<usize>,
+}
+
+/// The reason why an interruptible computation stopped before completion.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InterruptReason {
+    /// The `CancellationToken` of the budget was cancelled.
+    Cancelled,
+    /// The deadline of the budget has passed.
+    Timeout,
+    /// A BDD larger than the limit of the budget was created.
+    BddSizeExceeded,
+}
+
+/// An error returned by an interrupted computation, together with the partial result
+/// computed before the interruption.
+#[derive(Clone, Debug)]
+pub struct Interrupted<T> {
+    pub reason: InterruptReason,
+    pub partial: T,
+}
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel all computations which use this token (or one of its clones).
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// True if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Budget {
+    /// Create a new unlimited budget.
+    pub fn unlimited() -> Budget {
+        Budget::default()
+    }
+
+    /// Make a copy of this budget which is also interrupted by the given `token`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Budget {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Make a copy of this budget which expires after the given `timeout` (measured
+    /// from now).
+    pub fn with_timeout(self, timeout: Duration) -> Budget {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Make a copy of this budget which expires at the given `deadline`.
+    pub fn with_deadline(mut self, deadline: Instant) -> Budget {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Make a copy of this budget which is exhausted once a BDD with more than `max_size`
+    /// nodes is created.
+    pub fn with_max_bdd_size(mut self, max_size: usize) -> Budget {
+        self.max_bdd_size = Some(max_size);
+        self
+    }
+
+    /// Check that the budget is not exhausted, considering that `bdd` is the last
+    /// result of the computation.
+    pub fn check(&self, bdd: &Bdd) -> Result<(), InterruptReason> {
+        self.check_time()?;
+        match self.max_bdd_size {
+            Some(max_size) if bdd.size() > max_size => Err(InterruptReason::BddSizeExceeded),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the budget is not cancelled and the deadline has not passed yet.
+    pub fn check_time(&self) -> Result<(), InterruptReason> {
+        if let Some(token) = &self.cancellation {
+            if token.is_cancelled() {
+                return Err(InterruptReason::Cancelled);
+            }
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(InterruptReason::Timeout),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> Interrupted<T> {
+    /// Create a new `Interrupted` error with the given `reason` and `partial` result.
+    pub fn new(reason: InterruptReason, partial: T) -> Interrupted<T> {
+        Interrupted { reason, partial }
+    }
+
+    /// Transform the partial result of this error, keeping the reason.
+    pub fn map<R, F: FnOnce(T) -> R>(self, f: F) -> Interrupted<R> {
+        Interrupted {
+            reason: self.reason,
+            partial: f(self.partial),
+        }
+    }
+}
+
+impl Display for InterruptReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            InterruptReason::Cancelled => write!(f, "computation cancelled"),
+            InterruptReason::Timeout => write!(f, "time limit exceeded"),
+            InterruptReason::BddSizeExceeded => write!(f, "BDD size limit exceeded"),
+        }
+    }
+}
+
+impl<T> Display for Interrupted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Interrupted: {}.", self.reason)
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for Interrupted<T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::budget::{Budget, CancellationToken, InterruptReason};
+    use biodivine_lib_bdd::BddVariableSet;
+    use std::time::Duration;
+
+    #[test]
+    fn budget_limits() {
+        let vars = BddVariableSet::new_anonymous(3);
+        let bdd = vars.eval_expression_string("x_0 & x_1 & x_2");
+        assert_eq!(Ok(()), Budget::unlimited().check(&bdd));
+
+        let budget = Budget::unlimited().with_max_bdd_size(3);
+        assert_eq!(Ok(()), budget.check(&vars.mk_true()));
+        assert_eq!(Err(InterruptReason::BddSizeExceeded), budget.check(&bdd));
+
+        let budget = Budget::unlimited().with_timeout(Duration::from_secs(0));
+        assert_eq!(Err(InterruptReason::Timeout), budget.check(&bdd));
+
+        let token = CancellationToken::new();
+        let budget = Budget::unlimited().with_cancellation(token.clone());
+        assert_eq!(Ok(()), budget.check(&bdd));
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(Err(InterruptReason::Cancelled), budget.check(&bdd));
+    }
+}
@@ -0,0 +1,326 @@
+//! Attractor control, i.e. computation of perturbations which reprogram the network from
+//! a source state (or a set of states, e.g. an attractor) into a target attractor.
+//!
+//! A control is a set of variables together with the values that these variables are set to.
+//! We support three kinds of control:
+//!  - *One-step*: The values of the controlled variables are changed once, then the network
+//!    evolves freely.
+//!  - *Permanent*: The controlled variables are fixed to the chosen values forever
+//!    (as in `BooleanNetwork::fix_variables`).
+//!  - *Temporary*: The controlled variables are fixed until the perturbed network reaches
+//!    its attractor, then the perturbation is removed and the network evolves freely.
+//!
+//! A control is successful for a color if, starting in any of the source states, every
+//! attractor that the network can eventually reach intersects the target set. For a target
+//! attractor, this means the network always ends up in that attractor, i.e. the source is
+//! moved into the *strong basin* of the target. Permanent and temporary control need
+//! a graph created using `SymbolicAsyncGraph::with_perturbations`, such that all candidate
+//! perturbations are examined symbolically at once.
+//!
+//! All functions only return *minimal* controls (with respect to set inclusion) for each
+//! color, up to the given maximal size.
+
+use crate::biodivine_std::traits::Set;
+use crate::reachability::reach_bwd;
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use crate::VariableId;
+use biodivine_lib_bdd::Bdd;
+
+/// A perturbation together with the colors for which it successfully controls the network.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Control {
+    /// The controlled variables together with the values they are set to.
+    pub perturbation: Vec<(VariableId, bool)>,
+    /// The colors for which the perturbation is a minimal control.
+    pub colors: GraphColors,
+}
+
+/// Compute minimal one-step controls of size at most `max_size` which move every state of
+/// the `source` set into the strong basin of the `target`.
+///
+/// Only colors of the `source` set are considered.
+pub fn one_step_control(
+    graph: &SymbolicAsyncGraph,
+    source: &GraphColoredVertices,
+    target: &GraphColoredVertices,
+    max_size: usize,
+) -> Vec<Control> {
+    let basin = graph.strong_basin(target);
+    let variables: Vec<VariableId> = graph.as_network().variables().collect();
+    minimal_controls(&variables, max_size, |perturbation| {
+        let start = apply_perturbation(graph, source, perturbation);
+        controlled_colors(&start, &basin)
+    })
+}
+
+/// Compute minimal permanent controls of size at most `max_size` such that from every
+/// state of the `source` set, the perturbed network always reaches the `target`.
+///
+/// The `graph` must be created using `SymbolicAsyncGraph::with_perturbations` and only
+/// the perturbable variables are used as candidates. The colors of the returned controls
+/// are not restricted in terms of perturbation variables.
+pub fn permanent_control(
+    graph: &SymbolicAsyncGraph,
+    source: &GraphColoredVertices,
+    target: &GraphColoredVertices,
+    max_size: usize,
+) -> Vec<Control> {
+    let valid = graph.strong_basin(target);
+    let variables = perturbable_variables(graph);
+    minimal_controls(&variables, max_size, |perturbation| {
+        let start = apply_perturbation(graph, source, perturbation);
+        let start = start.intersect(&graph.fix_perturbation(perturbation));
+        forget_perturbations(graph, &controlled_colors(&start, &valid))
+    })
+}
+
+/// Compute minimal temporary controls of size at most `max_size` such that from every state
+/// of the `source` set, all attractors of the perturbed network lie in the strong basin
+/// of the `target` in the unperturbed network.
+///
+/// The `graph` must be created using `SymbolicAsyncGraph::with_perturbations` and only
+/// the perturbable variables are used as candidates. The colors of the returned controls
+/// are not restricted in terms of perturbation variables.
+pub fn temporary_control(
+    graph: &SymbolicAsyncGraph,
+    source: &GraphColoredVertices,
+    target: &GraphColoredVertices,
+    max_size: usize,
+) -> Vec<Control> {
+    let unperturbed = graph.mk_perturbed_colors(&[]);
+    let basin = graph.strong_basin(&target.intersect_colors(&unperturbed));
+    // The basin of the unperturbed network, lifted to all perturbations.
+    let basin = graph.unit_colored_vertices().copy(
+        graph
+            .unit_colored_vertices()
+            .as_bdd()
+            .and(&forget_perturbations_bdd(graph, basin.as_bdd())),
+    );
+    // All attractors reachable in the perturbed network must be subsets of the basin, i.e.
+    // every reachable state can reach a trap set inside the basin.
+    let valid = graph.strong_basin(&always(graph, &basin));
+    let variables = perturbable_variables(graph);
+    minimal_controls(&variables, max_size, |perturbation| {
+        let start = apply_perturbation(graph, source, perturbation);
+        let start = start.intersect(&graph.fix_perturbation(perturbation));
+        forget_perturbations(graph, &controlled_colors(&start, &valid))
+    })
+}
+
+/// **(internal)** The states from which every path stays in the given `set` (CTL `AG set`).
+/// This is the largest trap set contained in `set`.
+fn always(graph: &SymbolicAsyncGraph, set: &GraphColoredVertices) -> GraphColoredVertices {
+    let unit = graph.unit_colored_vertices();
+    unit.minus(&reach_bwd(graph, &unit.minus(set), unit))
+}
+
+/// **(internal)** Set the given variables of all states in the `set` to the given values.
+fn apply_perturbation(
+    graph: &SymbolicAsyncGraph,
+    set: &GraphColoredVertices,
+    perturbation: &[(VariableId, bool)],
+) -> GraphColoredVertices {
+    let state_variables = graph.symbolic_context().state_variables();
+    let mut bdd = set.as_bdd().clone();
+    for (var, value) in perturbation {
+        bdd = bdd
+            .var_project(state_variables[var.0])
+            .var_select(state_variables[var.0], *value);
+    }
+    set.copy(bdd)
+}
+
+/// **(internal)** The colors for which all vertices of `start` belong to the `valid` set.
+fn controlled_colors(start: &GraphColoredVertices, valid: &GraphColoredVertices) -> GraphColors {
+    start.colors().minus(&start.minus(valid).colors())
+}
+
+/// **(internal)** List the variables of the `graph` which can be perturbed.
+fn perturbable_variables(graph: &SymbolicAsyncGraph) -> Vec<VariableId> {
+    graph
+        .as_network()
+        .variables()
+        .filter(|var| graph.perturbation_variable(*var).is_some())
+        .collect()
+}
+
+/// **(internal)** Remove all perturbation variables from the given `colors`.
+fn forget_perturbations(graph: &SymbolicAsyncGraph, colors: &GraphColors) -> GraphColors {
+    colors.copy(forget_perturbations_bdd(graph, colors.as_bdd()))
+}
+
+/// **(internal)** Existentially quantify all perturbation variables in the given `bdd`.
+fn forget_perturbations_bdd(graph: &SymbolicAsyncGraph, bdd: &Bdd) -> Bdd {
+    graph
+        .as_network()
+        .variables()
+        .filter_map(|var| graph.perturbation_variable(var))
+        .fold(bdd.clone(), |bdd, var| bdd.var_project(var))
+}
+
+/// **(internal)** Test all perturbations of at most `max_size` of the given `variables`
+/// (in the order of increasing size) using the `controlled` function. Colors which are
+/// already controlled by a subset of a perturbation are not reported again, hence all
+/// returned controls are minimal.
+fn minimal_controls<F>(variables: &[VariableId], max_size: usize, mut controlled: F) -> Vec<Control>
+where
+    F: FnMut(&[(VariableId, bool)]) -> GraphColors,
+{
+    let mut result: Vec<Control> = Vec::new();
+    for size in 0..=max_size.min(variables.len()) {
+        for subset in combinations(variables, size) {
+            for values in 0..(1usize << size) {
+                let perturbation: Vec<(VariableId, bool)> = subset
+                    .iter()
+                    .enumerate()
+                    .map(|(i, var)| (*var, (values >> i) & 1 == 1))
+                    .collect();
+                let mut colors = controlled(&perturbation);
+                for smaller in &result {
+                    if smaller
+                        .perturbation
+                        .iter()
+                        .all(|it| perturbation.contains(it))
+                    {
+                        colors = colors.minus(&smaller.colors);
+                    }
+                }
+                if !colors.is_empty() {
+                    result.push(Control {
+                        perturbation,
+                        colors,
+                    });
+                }
+            }
+        }
+    }
+    result
+}
+
+/// **(internal)** All subsets of `items` of the given `size`, in lexicographic order.
+fn combinations(items: &[VariableId], size: usize) -> Vec<Vec<VariableId>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[(i + 1)..], size - 1) {
+            rest.insert(0, *item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::bitvector::ArrayBitVector;
+    use crate::biodivine_std::traits::Set;
+    use crate::control::{one_step_control, permanent_control, temporary_control};
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, VariableId};
+    use std::convert::TryFrom;
+
+    /// A bistable toggle switch with an output variable.
+    fn toggle_switch() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            a -> c
+            $a: !b
+            $b: !a
+            $c: a
+        ",
+        )
+        .unwrap()
+    }
+
+    fn state(values: &[bool]) -> ArrayBitVector {
+        ArrayBitVector::from(values.to_vec())
+    }
+
+    #[test]
+    fn one_step_control_of_toggle_switch() {
+        let stg = SymbolicAsyncGraph::new(toggle_switch()).unwrap();
+        let (a, b) = (VariableId(0), VariableId(1));
+        let source = stg.vertex(&state(&[false, true, false]));
+        let target = stg.vertex(&state(&[true, false, true]));
+
+        // The source is already in the target attractor.
+        let controls = one_step_control(&stg, &target, &target, 2);
+        assert_eq!(1, controls.len());
+        assert!(controls[0].perturbation.is_empty());
+
+        let controls = one_step_control(&stg, &source, &target, 3);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            controls.iter().map(|it| it.perturbation.clone()).collect();
+        assert_eq!(vec![vec![(a, true), (b, false)]], perturbations);
+        assert_eq!(stg.unit_colors(), &controls[0].colors);
+        assert!(one_step_control(&stg, &source, &target, 1).is_empty());
+    }
+
+    #[test]
+    fn permanent_and_temporary_control_of_toggle_switch() {
+        let bn = toggle_switch();
+        let variables: Vec<VariableId> = bn.variables().collect();
+        let (a, b, c) = (VariableId(0), VariableId(1), VariableId(2));
+        let stg = SymbolicAsyncGraph::with_perturbations(bn, &variables).unwrap();
+        let source = stg.vertex(&state(&[false, true, false]));
+        let target = stg.vertex(&state(&[true, false, true]));
+
+        // Fixing `a` or `b` is enough, fixing `c` changes nothing.
+        let permanent = permanent_control(&stg, &source, &target, 2);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            permanent.iter().map(|it| it.perturbation.clone()).collect();
+        assert_eq!(vec![vec![(a, true)], vec![(b, false)]], perturbations);
+
+        // Fixing `c` permanently would turn off `c` in the target attractor.
+        let c_target = stg.vertex(&state(&[true, false, false]));
+        let permanent = permanent_control(&stg, &source, &c_target, 2);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            permanent.iter().map(|it| it.perturbation.clone()).collect();
+        assert!(perturbations.contains(&vec![(a, true), (c, false)]));
+        assert!(perturbations.contains(&vec![(b, false), (c, false)]));
+
+        // After releasing `a` or `b`, the toggle switch stays in the target attractor.
+        let temporary = temporary_control(&stg, &source, &target, 2);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            temporary.iter().map(|it| it.perturbation.clone()).collect();
+        assert_eq!(vec![vec![(a, true)], vec![(b, false)]], perturbations);
+        for control in temporary {
+            assert!(control.colors.is_subset(stg.unit_colors()));
+            assert!(!control.colors.is_empty());
+        }
+    }
+
+    #[test]
+    fn temporary_control_is_not_permanent() {
+        // `a` is always turned off, but once `b` is on, it stays on.
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -> b
+            $a: false
+            $b: a | b
+        ",
+        )
+        .unwrap();
+        let variables: Vec<VariableId> = bn.variables().collect();
+        let (a, b) = (VariableId(0), VariableId(1));
+        let stg = SymbolicAsyncGraph::with_perturbations(bn, &variables).unwrap();
+        let source = stg.vertex(&state(&[false, false]));
+        let target = stg.vertex(&state(&[false, true]));
+
+        let temporary = temporary_control(&stg, &source, &target, 1);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            temporary.iter().map(|it| it.perturbation.clone()).collect();
+        assert_eq!(vec![vec![(a, true)], vec![(b, true)]], perturbations);
+
+        // Fixing `a` permanently leads to a different attractor.
+        let permanent = permanent_control(&stg, &source, &target, 1);
+        let perturbations: Vec<Vec<(VariableId, bool)>> =
+            permanent.iter().map(|it| it.perturbation.clone()).collect();
+        assert_eq!(vec![vec![(b, true)]], perturbations);
+    }
+}
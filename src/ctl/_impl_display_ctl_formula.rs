@@ -0,0 +1,50 @@
+use crate::ctl::CtlFormula::*;
+use crate::ctl::{BinaryTemporalOp, CtlFormula, HybridOp, UnaryTemporalOp};
+use std::fmt::{Display, Error, Formatter};
+
+impl Display for CtlFormula {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Const(value) => write!(f, "{}", value),
+            Var(name) => write!(f, "{}", name),
+            Not(inner) => write!(f, "!{}", inner),
+            Binary(op, l, r) => write!(f, "({} {} {})", l, op, r),
+            UnaryTemporal(op, inner) => write!(f, "{} {}", op, inner),
+            BinaryTemporal(op, l, r) => write!(f, "({} {} {})", l, op, r),
+            StateVar(name) => write!(f, "{{{}}}", name),
+            Hybrid(op, name, inner) => {
+                let symbol = match op {
+                    HybridOp::Bind => "!",
+                    HybridOp::Jump => "@",
+                    HybridOp::Exists => "3",
+                    HybridOp::Forall => "V",
+                };
+                write!(f, "({}{{{}}}: {})", symbol, name, inner)
+            }
+        }
+    }
+}
+
+impl Display for UnaryTemporalOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let symbol = match self {
+            UnaryTemporalOp::ExistsNext => "EX",
+            UnaryTemporalOp::AllNext => "AX",
+            UnaryTemporalOp::ExistsFuture => "EF",
+            UnaryTemporalOp::AllFuture => "AF",
+            UnaryTemporalOp::ExistsGlobal => "EG",
+            UnaryTemporalOp::AllGlobal => "AG",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+impl Display for BinaryTemporalOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let symbol = match self {
+            BinaryTemporalOp::ExistsUntil => "EU",
+            BinaryTemporalOp::AllUntil => "AU",
+        };
+        write!(f, "{}", symbol)
+    }
+}
@@ -0,0 +1,307 @@
+use crate::ctl::CtlFormula::*;
+use crate::ctl::{BinaryTemporalOp, CtlFormula, HybridOp, UnaryTemporalOp};
+use crate::BinaryOp;
+use std::convert::TryFrom;
+use std::iter::Peekable;
+use std::str::Chars;
+
+impl TryFrom<&str> for CtlFormula {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let tokens = tokenize_formula_group(&mut value.chars().peekable(), true)?;
+        Ok(*(parse_formula(&tokens)?))
+    }
+}
+
+/// **(internal)** An enum of possible tokens occurring in a string representation of
+/// a `CtlFormula`. Temporal operators are parsed as names and recognized later.
+#[derive(Debug, Eq, PartialEq)]
+enum Token {
+    Not,                // '!'
+    And,                // '&'
+    Or,                 // '|'
+    Xor,                // '^'
+    Imp,                // '=>'
+    Iff,                // '<=>'
+    At,                 // '@'
+    Colon,              // ':'
+    Name(String),       // 'name'
+    Tokens(Vec<Token>), // A block of tokens inside parentheses
+}
+
+/// **(internal)** Process a peekable iterator of characters into a vector of `Token`s.
+///
+/// The outer method always consumes the opening parenthesis and the recursive call consumes the
+/// closing parenthesis. Use `top_level` to indicate that there will be no closing parenthesis.
+fn tokenize_formula_group(
+    data: &mut Peekable<Chars>,
+    top_level: bool,
+) -> Result<Vec<Token>, String> {
+    let mut output = Vec::new();
+    while let Some(c) = data.next() {
+        match c {
+            c if c.is_whitespace() => { /* Skip whitespace */ }
+            '!' => output.push(Token::Not),
+            '&' => output.push(Token::And),
+            '|' => output.push(Token::Or),
+            '^' => output.push(Token::Xor),
+            '@' => output.push(Token::At),
+            ':' => output.push(Token::Colon),
+            '=' => {
+                if Some('>') == data.next() {
+                    output.push(Token::Imp);
+                } else {
+                    return Err("Expected '>' after '='.".to_string());
+                }
+            }
+            '<' => {
+                if Some('=') == data.next() && Some('>') == data.next() {
+                    output.push(Token::Iff);
+                } else {
+                    return Err("Expected '<=>'.".to_string());
+                }
+            }
+            ')' => {
+                return if !top_level {
+                    Ok(output)
+                } else {
+                    Err("Unexpected ')'.".to_string())
+                }
+            }
+            '(' => {
+                let tokens = tokenize_formula_group(data, false)?;
+                output.push(Token::Tokens(tokens));
+            }
+            c if is_valid_in_name(c) => {
+                let mut name = vec![c];
+                while let Some(c) = data.peek() {
+                    if !is_valid_in_name(*c) {
+                        break;
+                    }
+                    name.push(*c);
+                    data.next();
+                }
+                output.push(Token::Name(name.into_iter().collect()));
+            }
+            _ => return Err(format!("Unexpected '{}'.", c)),
+        }
+    }
+    if top_level {
+        Ok(output)
+    } else {
+        Err("Expected ')'.".to_string())
+    }
+}
+
+/// **(internal)** Check if given char can appear in a name.
+fn is_valid_in_name(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '{' || c == '}'
+}
+
+/// **(internal)** Try to read a unary temporal operator from a token.
+fn unary_temporal(token: &Token) -> Option<UnaryTemporalOp> {
+    match token {
+        Token::Name(name) => match name.as_str() {
+            "EX" => Some(UnaryTemporalOp::ExistsNext),
+            "AX" => Some(UnaryTemporalOp::AllNext),
+            "EF" => Some(UnaryTemporalOp::ExistsFuture),
+            "AF" => Some(UnaryTemporalOp::AllFuture),
+            "EG" => Some(UnaryTemporalOp::ExistsGlobal),
+            "AG" => Some(UnaryTemporalOp::AllGlobal),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// **(internal)** Try to read a binary temporal operator from a token.
+fn binary_temporal(token: &Token) -> Option<BinaryTemporalOp> {
+    match token {
+        Token::Name(name) if name == "EU" => Some(BinaryTemporalOp::ExistsUntil),
+        Token::Name(name) if name == "AU" => Some(BinaryTemporalOp::AllUntil),
+        _ => None,
+    }
+}
+
+/// **(internal)** Read the name of a state variable from a `{name}` string.
+fn state_variable(name: &str) -> Option<String> {
+    if name.len() > 2 && name.starts_with('{') && name.ends_with('}') {
+        let inner = &name[1..(name.len() - 1)];
+        if !inner.contains(['{', '}']) {
+            return Some(inner.to_string());
+        }
+    }
+    None
+}
+
+/// **(internal)** Try to read a hybrid operator prefix (e.g. `!{x}:`) starting at
+/// the given `index`. Returns the operator, the state variable and the length of the prefix.
+fn hybrid_prefix(data: &[Token], index: usize) -> Option<(HybridOp, String, usize)> {
+    let data = &data[index..];
+    if let [op, Token::Name(name), Token::Colon, ..] = data {
+        let op = match op {
+            Token::Not => HybridOp::Bind,
+            Token::At => HybridOp::Jump,
+            _ => return None,
+        };
+        return state_variable(name).map(|name| (op, name, 3));
+    }
+    if let [Token::Name(name), Token::Colon, ..] = data {
+        let op = if name.starts_with('3') {
+            HybridOp::Exists
+        } else if name.starts_with('V') {
+            HybridOp::Forall
+        } else {
+            return None;
+        };
+        return state_variable(&name[1..]).map(|name| (op, name, 2));
+    }
+    None
+}
+
+/// **(internal)** Hybrid operators extend to the end of the formula, hence other binary
+/// operators can only appear before the first hybrid prefix.
+fn hybrid_scope(data: &[Token]) -> usize {
+    (0..data.len())
+        .find(|i| hybrid_prefix(data, *i).is_some())
+        .unwrap_or(data.len())
+}
+
+/// **(internal)** Parse a `CtlFormula` using the recursive steps.
+fn parse_formula(data: &[Token]) -> Result<Box<CtlFormula>, String> {
+    binary(data, 0)
+}
+
+/// **(internal)** Boolean operators, ordered from the weakest to the strongest.
+const BINARY_OPERATORS: [(Token, BinaryOp); 5] = [
+    (Token::Iff, BinaryOp::Iff),
+    (Token::Imp, BinaryOp::Imp),
+    (Token::Or, BinaryOp::Or),
+    (Token::And, BinaryOp::And),
+    (Token::Xor, BinaryOp::Xor),
+];
+
+/// **(internal)** Recursive parsing step: extract the Boolean operator with the given
+/// `level` (index into `BINARY_OPERATORS`) and continue with the next level on both sides.
+fn binary(data: &[Token], level: usize) -> Result<Box<CtlFormula>, String> {
+    if level == BINARY_OPERATORS.len() {
+        return temporal(data);
+    }
+    let (token, op) = &BINARY_OPERATORS[level];
+    let scope = &data[..hybrid_scope(data)];
+    Ok(if let Some(i) = scope.iter().position(|t| t == token) {
+        let left = binary(&data[..i], level + 1)?;
+        Box::new(Binary(*op, left, binary(&data[(i + 1)..], level)?))
+    } else {
+        binary(data, level + 1)?
+    })
+}
+
+/// **(internal)** Recursive parsing step: extract binary temporal operators.
+fn temporal(data: &[Token]) -> Result<Box<CtlFormula>, String> {
+    let scope = &data[..hybrid_scope(data)];
+    Ok(
+        if let Some(i) = scope.iter().position(|t| binary_temporal(t).is_some()) {
+            let op = binary_temporal(&data[i]).unwrap();
+            Box::new(BinaryTemporal(
+                op,
+                terminal(&data[..i])?,
+                temporal(&data[(i + 1)..])?,
+            ))
+        } else {
+            terminal(data)?
+        },
+    )
+}
+
+/// **(internal)** Recursive parsing step: extract terminals and unary operators.
+fn terminal(data: &[Token]) -> Result<Box<CtlFormula>, String> {
+    if data.is_empty() {
+        return Err("Expected formula, found nothing.".to_string());
+    }
+    if let Some((op, name, length)) = hybrid_prefix(data, 0) {
+        return Ok(Box::new(Hybrid(op, name, parse_formula(&data[length..])?)));
+    }
+    if data[0] == Token::Not {
+        return Ok(Box::new(Not(terminal(&data[1..])?)));
+    }
+    if let Some(op) = unary_temporal(&data[0]) {
+        return Ok(Box::new(UnaryTemporal(op, terminal(&data[1..])?)));
+    }
+    if data.len() == 1 {
+        match &data[0] {
+            Token::Name(name) if binary_temporal(&data[0]).is_none() => {
+                return Ok(Box::new(match name.as_str() {
+                    "true" => Const(true),
+                    "false" => Const(false),
+                    _ => match state_variable(name) {
+                        Some(name) => StateVar(name),
+                        None => Var(name.clone()),
+                    },
+                }));
+            }
+            Token::Tokens(inner) => return parse_formula(inner),
+            _ => {}
+        }
+    }
+    Err(format!("Unexpected: {:?}. Expecting formula.", data))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ctl::CtlFormula;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_ctl_formula() {
+        let inputs = vec![
+            "var",
+            "!var",
+            "EF Apoptosis",
+            "AG (a & !b)",
+            "(a EU (b | c))",
+            "!AX EG (a => b)",
+            "((a AU b) <=> EF true)",
+            "(a ^ (b & c))",
+        ];
+        for str in inputs {
+            assert_eq!(str, format!("{}", CtlFormula::try_from(str).unwrap()));
+        }
+
+        // Binary operators bind weaker than temporal operators.
+        let formula = CtlFormula::try_from("EF a & b EU c").unwrap();
+        assert_eq!("(EF a & (b EU c))", formula.to_string());
+
+        let invalid = vec!["", "a &", "EF", "(a", "a)", "a EU", "a b", "a = b", "EU"];
+        for str in invalid {
+            assert!(CtlFormula::try_from(str).is_err(), "{}", str);
+        }
+    }
+
+    #[test]
+    fn parse_hybrid_formula() {
+        let inputs = vec![
+            "{x}",
+            "(!{x}: AG EF {x})",
+            "(3{x}: (V{y}: (@{x}: !{y})))",
+            "(a & (!{x}: (b | {x})))",
+            "EF (@{s}: (a EU {s}))",
+        ];
+        for str in inputs {
+            assert_eq!(str, format!("{}", CtlFormula::try_from(str).unwrap()));
+        }
+
+        // Hybrid operators extend as far as possible.
+        let formula = CtlFormula::try_from("a & !{x}: b | 3{y}: {x} & !{y}").unwrap();
+        assert_eq!(
+            "(a & (!{x}: (b | (3{y}: ({x} & !{y})))))",
+            formula.to_string()
+        );
+
+        let invalid = vec!["!{x}:", "@{}: a", "3{x} a", "a & @{x}"];
+        for str in invalid {
+            assert!(CtlFormula::try_from(str).is_err(), "{}", str);
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use crate::biodivine_std::traits::Set;
+use crate::ctl::CtlFormula::*;
+use crate::ctl::{BinaryTemporalOp, CtlFormula, HybridOp, ModelChecker, UnaryTemporalOp};
+use crate::fixed_points::fixed_points;
+use crate::reachability::reach_bwd;
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+use crate::BinaryOp;
+
+impl CtlFormula {
+    /// Compute the colored vertices of the `graph` which satisfy this formula.
+    ///
+    /// Returns an error if the formula references a variable which is not in the network,
+    /// contains a free hybrid state variable, or if the `graph` does not have enough extra
+    /// state variables to represent all hybrid state variables of the formula.
+    pub fn eval(&self, graph: &SymbolicAsyncGraph) -> Result<GraphColoredVertices, String> {
+        check_bound_variables(self, &mut Vec::new())?;
+        let hybrid_variables = self.hybrid_variables();
+        let network = graph.as_network();
+        if let Some(variable) = network.variables().next() {
+            let available = graph
+                .symbolic_context()
+                .extra_state_variables(variable)
+                .len();
+            if available < hybrid_variables.len() {
+                return Err(format!(
+                    "The formula uses {} state variables, but the graph only supports {}.",
+                    hybrid_variables.len(),
+                    available
+                ));
+            }
+        }
+        ModelChecker::new(graph, hybrid_variables).eval(self)
+    }
+
+    /// Distinct names of the hybrid state variables used in this formula (in the order
+    /// of their first appearance).
+    pub fn hybrid_variables(&self) -> Vec<String> {
+        fn collect(formula: &CtlFormula, result: &mut Vec<String>) {
+            match formula {
+                Const(_) | Var(_) => {}
+                StateVar(name) => {
+                    if !result.contains(name) {
+                        result.push(name.clone());
+                    }
+                }
+                Hybrid(_, name, inner) => {
+                    if !result.contains(name) {
+                        result.push(name.clone());
+                    }
+                    collect(inner, result);
+                }
+                Not(inner) | UnaryTemporal(_, inner) => collect(inner, result),
+                Binary(_, l, r) | BinaryTemporal(_, l, r) => {
+                    collect(l, result);
+                    collect(r, result);
+                }
+            }
+        }
+        let mut result = Vec::new();
+        collect(self, &mut result);
+        result
+    }
+}
+
+/// **(internal)** Check that every state variable is bound by some hybrid operator.
+fn check_bound_variables(formula: &CtlFormula, bound: &mut Vec<String>) -> Result<(), String> {
+    match formula {
+        Const(_) | Var(_) => Ok(()),
+        StateVar(name) | Hybrid(HybridOp::Jump, name, _) if !bound.contains(name) => {
+            Err(format!("State variable {{{}}} is not bound.", name))
+        }
+        StateVar(_) => Ok(()),
+        Hybrid(_, name, inner) => {
+            bound.push(name.clone());
+            let result = check_bound_variables(inner, bound);
+            bound.pop();
+            result
+        }
+        Not(inner) | UnaryTemporal(_, inner) => check_bound_variables(inner, bound),
+        Binary(_, l, r) | BinaryTemporal(_, l, r) => {
+            check_bound_variables(l, bound)?;
+            check_bound_variables(r, bound)
+        }
+    }
+}
+
+impl ModelChecker<'_> {
+    pub fn new(graph: &SymbolicAsyncGraph, hybrid_variables: Vec<String>) -> ModelChecker<'_> {
+        ModelChecker {
+            graph,
+            fixed_points: fixed_points(graph),
+            hybrid_variables,
+        }
+    }
+
+    pub fn eval(&self, formula: &CtlFormula) -> Result<GraphColoredVertices, String> {
+        Ok(match formula {
+            Const(true) => self.graph.mk_unit_colored_vertices(),
+            Const(false) => self.graph.mk_empty_vertices(),
+            Var(name) => {
+                let network = self.graph.as_network();
+                let variable = network
+                    .as_graph()
+                    .find_variable(name)
+                    .ok_or_else(|| format!("Unknown variable `{}`.", name))?;
+                self.graph.fix_network_variable(variable, true)
+            }
+            Not(inner) => self.not(&self.eval(inner)?),
+            Binary(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                match op {
+                    BinaryOp::And => left.intersect(&right),
+                    BinaryOp::Or => left.union(&right),
+                    BinaryOp::Xor => left.minus(&right).union(&right.minus(&left)),
+                    BinaryOp::Imp => self.not(&left).union(&right),
+                    BinaryOp::Iff => {
+                        let both = left.intersect(&right);
+                        let neither = self.not(&left.union(&right));
+                        both.union(&neither)
+                    }
+                }
+            }
+            UnaryTemporal(op, inner) => {
+                let inner = self.eval(inner)?;
+                match op {
+                    UnaryTemporalOp::ExistsNext => self.ex(&inner),
+                    UnaryTemporalOp::AllNext => self.not(&self.ex(&self.not(&inner))),
+                    UnaryTemporalOp::ExistsFuture => {
+                        self.eu(self.graph.unit_colored_vertices(), &inner)
+                    }
+                    UnaryTemporalOp::AllFuture => self.not(&self.eg(&self.not(&inner))),
+                    UnaryTemporalOp::ExistsGlobal => self.eg(&inner),
+                    UnaryTemporalOp::AllGlobal => {
+                        let unit = self.graph.unit_colored_vertices();
+                        self.not(&self.eu(unit, &self.not(&inner)))
+                    }
+                }
+            }
+            BinaryTemporal(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                match op {
+                    BinaryTemporalOp::ExistsUntil => self.eu(&left, &right),
+                    BinaryTemporalOp::AllUntil => self.au(&left, &right),
+                }
+            }
+            StateVar(name) => {
+                let equal = self.mk_state_is_variable(name);
+                self.graph.unit_colored_vertices().intersect(&equal)
+            }
+            Hybrid(op, name, inner) => {
+                let inner = self.eval(inner)?;
+                let context = self.graph.symbolic_context();
+                let offset = self.offset(name);
+                match op {
+                    HybridOp::Bind => {
+                        let bound = inner.intersect(&self.mk_state_is_variable(name));
+                        let bound = bound
+                            .as_bdd()
+                            .project(&context.all_extra_state_variables(offset));
+                        GraphColoredVertices::new(bound, context)
+                    }
+                    HybridOp::Jump => {
+                        let jump = inner.intersect(&self.mk_state_is_variable(name));
+                        let jump = jump.as_bdd().project(context.state_variables());
+                        self.graph
+                            .unit_colored_vertices()
+                            .intersect(&GraphColoredVertices::new(jump, context))
+                    }
+                    HybridOp::Exists => self.exists(offset, &inner),
+                    HybridOp::Forall => self.not(&self.exists(offset, &self.not(&inner))),
+                }
+            }
+        })
+    }
+
+    /// **(internal)** Offset of the extra BDD variables representing the given state variable.
+    fn offset(&self, name: &str) -> usize {
+        self.hybrid_variables
+            .iter()
+            .position(|it| it == name)
+            .unwrap()
+    }
+
+    /// The relation between the network state and the state variable `name`, i.e.
+    /// the (unconstrained) set where these two states are equal.
+    fn mk_state_is_variable(&self, name: &str) -> GraphColoredVertices {
+        let context = self.graph.symbolic_context();
+        let offset = self.offset(name);
+        let equal = self.graph.as_network().variables().fold(
+            context.mk_constant(true),
+            |result, variable| {
+                let state = context.mk_state_variable_is_true(variable);
+                let extra = context.mk_extra_state_variable_is_true(variable, offset);
+                result.and(&state.iff(&extra))
+            },
+        );
+        GraphColoredVertices::new(equal, context)
+    }
+
+    /// **(internal)** Existential quantification over the state variable at `offset`.
+    fn exists(&self, offset: usize, set: &GraphColoredVertices) -> GraphColoredVertices {
+        let context = self.graph.symbolic_context();
+        let result = set
+            .as_bdd()
+            .project(&context.all_extra_state_variables(offset));
+        GraphColoredVertices::new(result, context)
+    }
+
+    /// Complement of the `set` with respect to the whole graph.
+    pub fn not(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        self.graph.unit_colored_vertices().minus(set)
+    }
+
+    /// Vertices which have a successor in the `set`.
+    pub fn ex(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        self.graph
+            .pre(set)
+            .union(&set.intersect(&self.fixed_points))
+    }
+
+    /// Vertices from which a path through `phi` reaches `psi` (least fixed point, computed
+    /// using saturation).
+    pub fn eu(
+        &self,
+        phi: &GraphColoredVertices,
+        psi: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        reach_bwd(self.graph, psi, &phi.union(psi))
+    }
+
+    /// Vertices with an infinite path in `phi` (greatest fixed point).
+    pub fn eg(&self, phi: &GraphColoredVertices) -> GraphColoredVertices {
+        let mut result = phi.clone();
+        loop {
+            let next = result.intersect(&self.ex(&result));
+            if next == result {
+                return result;
+            }
+            result = next;
+        }
+    }
+
+    /// Vertices where on all paths, `phi` holds until `psi` holds.
+    ///
+    /// Uses the identity `A[phi U psi] = !(E[!psi U (!phi & !psi)] | EG !psi)`.
+    pub fn au(
+        &self,
+        phi: &GraphColoredVertices,
+        psi: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        let not_psi = self.not(psi);
+        let violated = self.eu(&not_psi, &not_psi.minus(phi));
+        self.not(&violated.union(&self.eg(&not_psi)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::bitvector::ArrayBitVector;
+    use crate::biodivine_std::traits::Set;
+    use crate::ctl::model_check;
+    use crate::decomposition::attractors;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn ctl_toggle_switch() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            a -> c
+            b -> c
+            $a: !b
+            $b: !a
+            $c: a & b
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let unit = stg.unit_colored_vertices();
+        let check = |formula: &str| model_check(&stg, formula).unwrap();
+
+        // Every trajectory eventually reaches one of the two stable states.
+        assert_eq!(unit, &check("AF (a ^ b)"));
+        assert_eq!(check("a ^ b"), check("AG (a ^ b)"));
+        let expected = check("(a ^ b) | (!a & !b & !c)");
+        assert_eq!(expected, check("AX (a ^ b) & AX !c"));
+        // Fixed points satisfy EX of themselves.
+        let fixed = stg.vertex(&ArrayBitVector::from(vec![true, false, false]));
+        assert!(fixed.is_subset(&check("EX (a & !b & !c)")));
+        assert!(fixed.is_subset(&check("EG a")));
+        // From (1,1,0), both stable states are reachable, but neither is inevitable.
+        let state = stg.vertex(&ArrayBitVector::from(vec![true, true, false]));
+        assert!(state.is_subset(&check("EF (a & !b & !c) & EF (!a & b & !c)")));
+        assert!(state.intersect(&check("AF (a & !b)")).is_empty());
+        assert!(state.is_subset(&check("(a <=> b) AU (a ^ b)")));
+        assert!(state.is_subset(&check("c EU (a & b)")));
+
+        assert!(model_check(&stg, "EF d").is_err());
+        assert!(model_check(&stg, "EF (a").is_err());
+    }
+
+    #[test]
+    fn ctl_agrees_with_reachability() {
+        let stg = SymbolicAsyncGraph::new(tutorial_network()).unwrap();
+        let check = |formula: &str| model_check(&stg, formula).unwrap();
+
+        let target = check("A & !B");
+        let mut reach = target.clone();
+        loop {
+            let predecessors = stg.pre(&reach);
+            if predecessors.is_subset(&reach) {
+                break;
+            }
+            reach = reach.union(&predecessors);
+        }
+        assert_eq!(reach, check("EF (A & !B)"));
+        assert_eq!(
+            stg.mk_unit_colored_vertices().minus(&reach),
+            check("AG !(A & !B)")
+        );
+        assert_eq!(check("true EU (A & !B)"), check("EF (A & !B)"));
+        assert_eq!(check("true AU C"), check("AF C"));
+        assert!(check("AF C").is_subset(&check("EF C")));
+        assert!(check("AG C").is_subset(&check("EG C")));
+        assert!(check("EG C").is_subset(&check("C")));
+        assert_eq!(check("A => B"), check("!A | B"));
+        assert_eq!(check("A <=> B"), check("!(A ^ B)"));
+    }
+
+    #[test]
+    fn hctl_attractors() {
+        let bn = tutorial_network();
+        let stg = SymbolicAsyncGraph::with_extra_state_variables(bn.clone(), 2).unwrap();
+        let check = |formula: &str| model_check(&stg, formula).unwrap();
+        let in_attractor = attractors(&stg, stg.unit_colored_vertices())
+            .iter()
+            .fold(stg.mk_empty_vertices(), |a, b| a.union(b));
+        assert_eq!(in_attractor, check("!{x}: AG EF {x}"));
+        assert_eq!(in_attractor, check("3{y}: {y} & @{y}: !{x}: AG EF {x}"));
+
+        // Only the toggle switch (b = !a) has two attractors.
+        let toggle = BooleanNetwork::try_from("a -?? b \n b -| a \n $a: !b").unwrap();
+        let toggle = SymbolicAsyncGraph::with_extra_state_variables(toggle, 2).unwrap();
+        let toggle_attractors = attractors(&toggle, toggle.unit_colored_vertices());
+        let mut two_attractors = toggle.mk_empty_colors();
+        for (i, first) in toggle_attractors.iter().enumerate() {
+            for second in toggle_attractors.iter().skip(i + 1) {
+                let both = first.colors().intersect(&second.colors());
+                two_attractors = two_attractors.union(&both);
+            }
+        }
+        assert_eq!(1.0, two_attractors.approx_cardinality());
+        let formula = "3{x}: 3{y}: (@{x}: !EF {y} & AG EF {x}) & (@{y}: AG EF {y})";
+        let result = model_check(&toggle, formula).unwrap();
+        assert_eq!(two_attractors, result.colors());
+        let expected = toggle
+            .unit_colored_vertices()
+            .intersect_colors(&two_attractors);
+        assert_eq!(expected, result);
+
+        assert!(model_check(&stg, "{x}").is_err());
+        assert!(model_check(&stg, "@{x}: A").is_err());
+        assert!(model_check(&stg, "3{x}: 3{y}: 3{z}: {x} & {y} & {z}").is_err());
+        let small = SymbolicAsyncGraph::with_extra_state_variables(bn, 1).unwrap();
+        assert!(model_check(&small, formula).is_err());
+    }
+}
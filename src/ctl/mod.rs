@@ -0,0 +1,92 @@
+//! Symbolic CTL model checking on the `SymbolicAsyncGraph`.
+//!
+//! A `CtlFormula` can be parsed from a string which references network variables by name.
+//! Boolean operators use the same syntax as update functions (`!`, `&`, `|`, `^`, `=>`, `<=>`),
+//! the temporal operators are written as `EX`, `AX`, `EF`, `AF`, `EG`, `AG` (unary prefix
+//! operators) and `EU`, `AU` (binary infix operators, e.g. `(a EU b)`). Binary temporal
+//! operators bind stronger than Boolean operators, unary operators bind the strongest
+//! (parentheses are recommended).
+//!
+//! The result of model checking is the set of colored vertices satisfying the formula.
+//! To obtain a total transition relation (as required by CTL), every fixed point of
+//! the graph is considered to have a self-loop.
+//!
+//! Formulas can also use the operators of hybrid CTL (HCTL), which work with named state
+//! variables: `!{x}: phi` binds `x` to the current state, `@{x}: phi` evaluates `phi` in the
+//! state `x`, `3{x}: phi` and `V{x}: phi` quantify over all states `x`, and the atom `{x}`
+//! holds exactly in the state `x`. Hybrid operators extend as far to the right as possible.
+//! For example, the following formula holds when there are two distinct attractors:
+//! `3{x}: 3{y}: (@{x}: !EF {y} & AG EF {x}) & (@{y}: AG EF {y})`.
+//!
+//! Every state variable is represented by one copy of the network state variables, i.e. the
+//! `graph` must be created using `SymbolicAsyncGraph::with_extra_state_variables` with at
+//! least `CtlFormula::hybrid_variables().len()` extra variables.
+
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+use crate::BinaryOp;
+use std::convert::TryFrom;
+
+/// **(internal)** Implements `Display` for `CtlFormula`.
+mod _impl_display_ctl_formula;
+/// **(internal)** Implements `CtlFormula` parsing.
+mod _impl_from_string_for_ctl_formula;
+/// **(internal)** Symbolic evaluation of `CtlFormula`.
+mod _impl_model_checking;
+
+/// A CTL (or HCTL) formula over the variables of a `BooleanNetwork` (referenced by name).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum CtlFormula {
+    Const(bool),
+    Var(String),
+    Not(Box<CtlFormula>),
+    Binary(BinaryOp, Box<CtlFormula>, Box<CtlFormula>),
+    UnaryTemporal(UnaryTemporalOp, Box<CtlFormula>),
+    BinaryTemporal(BinaryTemporalOp, Box<CtlFormula>, Box<CtlFormula>),
+    StateVar(String),
+    Hybrid(HybridOp, String, Box<CtlFormula>),
+}
+
+/// Unary temporal operators of CTL.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnaryTemporalOp {
+    ExistsNext,   // EX
+    AllNext,      // AX
+    ExistsFuture, // EF
+    AllFuture,    // AF
+    ExistsGlobal, // EG
+    AllGlobal,    // AG
+}
+
+/// Binary temporal operators of CTL.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryTemporalOp {
+    ExistsUntil, // EU
+    AllUntil,    // AU
+}
+
+/// Hybrid operators of HCTL, each associated with one state variable.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HybridOp {
+    Bind,   // !{x}:
+    Jump,   // @{x}:
+    Exists, // 3{x}:
+    Forall, // V{x}:
+}
+
+/// Parse the given CTL `formula` and compute the colored vertices of the `graph` which
+/// satisfy it.
+pub fn model_check(
+    graph: &SymbolicAsyncGraph,
+    formula: &str,
+) -> Result<GraphColoredVertices, String> {
+    CtlFormula::try_from(formula)?.eval(graph)
+}
+
+/// **(internal)** Evaluates CTL operators on a fixed graph.
+struct ModelChecker<'a> {
+    graph: &'a SymbolicAsyncGraph,
+    // Fixed points of the graph, which are treated as having a self-loop.
+    fixed_points: GraphColoredVertices,
+    // Hybrid state variables, the index is the offset of their extra BDD variables.
+    hybrid_variables: Vec<String>,
+}
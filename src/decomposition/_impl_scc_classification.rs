@@ -0,0 +1,273 @@
+use crate::biodivine_std::traits::Set;
+use crate::decomposition::{
+    attractor_behaviour, ClassifiedScc, SccBehaviour, SccClassification, SccKind,
+};
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use std::fmt::{Display, Error, Formatter};
+
+impl ClassifiedScc {
+    /// The colored set of vertices of this component.
+    pub fn set(&self) -> &GraphColoredVertices {
+        &self.set
+    }
+
+    /// Colors in which this component exists.
+    pub fn colors(&self) -> GraphColors {
+        self.set.colors()
+    }
+
+    /// Position of this component in the graph of SCCs.
+    pub fn kind(&self) -> SccKind {
+        self.kind
+    }
+
+    /// Behaviour of the network inside this component.
+    pub fn behaviour(&self) -> SccBehaviour {
+        self.behaviour
+    }
+
+    /// True if this component is an attractor.
+    pub fn is_attractor(&self) -> bool {
+        self.kind == SccKind::Attractor
+    }
+}
+
+impl SccClassification<'_> {
+    /// Create a new empty classification for components of the given `graph`.
+    pub fn new(graph: &SymbolicAsyncGraph) -> SccClassification<'_> {
+        SccClassification {
+            graph,
+            components: Vec::new(),
+        }
+    }
+
+    /// Classify and add a new colored `scc`. For each of its colors, the set must be exactly
+    /// one SCC of the graph.
+    pub fn push(&mut self, scc: &GraphColoredVertices) {
+        let colors = scc.colors();
+        let has_successors = self.graph.post(scc).minus(scc).colors();
+        let has_predecessors = self.graph.pre(scc).minus(scc).colors();
+        let sink = colors.minus(&has_successors);
+        let source = colors.minus(&has_predecessors).minus(&sink);
+        let transient = colors.minus(&sink).minus(&source);
+
+        // Attractors are classified precisely, other components only by their size.
+        let attractor = scc.intersect_colors(&sink);
+        for (behaviour, colors) in attractor_behaviour(self.graph, &attractor) {
+            self.insert(scc.intersect_colors(&colors), SccKind::Attractor, behaviour);
+        }
+
+        let oscillating = scc.minus(&scc.pick_vertex()).colors();
+        for (kind, kind_colors) in &[(SccKind::Source, source), (SccKind::Transient, transient)] {
+            let set = scc.intersect_colors(kind_colors);
+            let stable = set.minus_colors(&oscillating);
+            self.insert(stable, *kind, SccBehaviour::Stable);
+            let oscillating = set.intersect_colors(&oscillating);
+            self.insert(oscillating, *kind, SccBehaviour::Oscillating);
+        }
+    }
+
+    /// **(internal)** Add a classified component, unless it is empty.
+    fn insert(&mut self, set: GraphColoredVertices, kind: SccKind, behaviour: SccBehaviour) {
+        if !set.is_empty() {
+            self.components.push(ClassifiedScc {
+                set,
+                kind,
+                behaviour,
+            });
+        }
+    }
+
+    /// Add all components of the `other` classification into this one (e.g. to combine
+    /// results of parallel workers). The components of both classifications must be disjoint.
+    pub fn merge(&mut self, other: &SccClassification) {
+        self.components.extend(other.components.iter().cloned());
+    }
+
+    /// All classified components.
+    pub fn components(&self) -> &[ClassifiedScc] {
+        &self.components
+    }
+
+    /// Colors which admit exactly `count` components satisfying the given `filter`.
+    pub fn colors_with<F>(&self, count: usize, filter: F) -> GraphColors
+    where
+        F: Fn(&ClassifiedScc) -> bool,
+    {
+        let counts = self.count_by(filter);
+        counts
+            .get(count)
+            .cloned()
+            .unwrap_or_else(|| self.graph.mk_empty_colors())
+    }
+
+    /// Colors which admit exactly `count` components.
+    pub fn colors_with_components(&self, count: usize) -> GraphColors {
+        self.colors_with(count, |_| true)
+    }
+
+    /// Colors which admit exactly `count` attractors.
+    pub fn colors_with_attractors(&self, count: usize) -> GraphColors {
+        self.colors_with(count, |it| it.is_attractor())
+    }
+
+    /// The largest number of components admitted by a single color.
+    pub fn max_components(&self) -> usize {
+        self.count_by(|_| true).len() - 1
+    }
+
+    /// Partition the colors of the graph based on the components they admit. Every item
+    /// of the result is a (non-empty) set of colors together with all components admitted
+    /// by these colors.
+    pub fn partition(&self) -> Vec<(GraphColors, Vec<&ClassifiedScc>)> {
+        let mut partition = vec![(self.graph.mk_unit_colors(), Vec::new())];
+        for component in &self.components {
+            let colors = component.colors();
+            let mut refined = Vec::new();
+            for (class, members) in partition {
+                let outside = class.minus(&colors);
+                if !outside.is_empty() {
+                    refined.push((outside, members.clone()));
+                }
+                let inside = class.intersect(&colors);
+                if !inside.is_empty() {
+                    let mut members = members;
+                    members.push(component);
+                    refined.push((inside, members));
+                }
+            }
+            partition = refined;
+        }
+        partition
+    }
+
+    /// **(internal)** Compute a vector where the i-th item is the set of colors with exactly
+    /// i components satisfying the `filter`. The last item is never empty.
+    fn count_by<F>(&self, filter: F) -> Vec<GraphColors>
+    where
+        F: Fn(&ClassifiedScc) -> bool,
+    {
+        let mut counts = vec![self.graph.mk_unit_colors()];
+        for component in self.components.iter().filter(|it| filter(it)) {
+            let mut colors = component.colors();
+            for i in (0..counts.len()).rev() {
+                let move_up = counts[i].intersect(&colors);
+                if move_up.is_empty() {
+                    continue;
+                }
+                colors = colors.minus(&move_up);
+                counts[i] = counts[i].minus(&move_up);
+                if i + 1 == counts.len() {
+                    counts.push(move_up);
+                } else {
+                    counts[i + 1] = counts[i + 1].union(&move_up);
+                }
+                if colors.is_empty() {
+                    break;
+                }
+            }
+        }
+        counts
+    }
+}
+
+impl Display for SccClassification<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "Classification output:")?;
+        for (i, colors) in self.count_by(|_| true).iter().enumerate() {
+            if !colors.is_empty() {
+                writeln!(f, "{} SCCs: {}", i, colors.approx_cardinality())?;
+            }
+        }
+        for (i, colors) in self.count_by(|it| it.is_attractor()).iter().enumerate() {
+            if !colors.is_empty() {
+                writeln!(f, "{} attractors: {}", i, colors.approx_cardinality())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::traits::Set;
+    use crate::decomposition::{
+        attractors, baseline_fwd_bwd, DecompositionOptions, SccBehaviour, SccClassification,
+        SccKind,
+    };
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use std::sync::Mutex;
+
+    #[test]
+    fn classify_tutorial_network() {
+        let stg = SymbolicAsyncGraph::new(tutorial_network()).unwrap();
+        let options = DecompositionOptions {
+            report_trivial: true,
+            ..DecompositionOptions::default()
+        };
+        let components = Mutex::new(Vec::new());
+        baseline_fwd_bwd(&stg, &options, |scc| components.lock().unwrap().push(scc)).unwrap();
+        let components = components.into_inner().unwrap();
+
+        let mut classification = SccClassification::new(&stg);
+        let mut first = SccClassification::new(&stg);
+        let mut second = SccClassification::new(&stg);
+        for (i, scc) in components.iter().enumerate() {
+            classification.push(scc);
+            if i % 2 == 0 {
+                first.push(scc);
+            } else {
+                second.push(scc);
+            }
+        }
+        first.merge(&second);
+        assert_eq!(classification.components().len(), first.components().len());
+
+        // Attractors agree with the attractor search.
+        let attractors = attractors(&stg, stg.unit_colored_vertices());
+        let found = classification
+            .components()
+            .iter()
+            .filter(|it| it.is_attractor())
+            .fold(stg.mk_empty_vertices(), |a, b| a.union(b.set()));
+        let expected = attractors
+            .iter()
+            .fold(stg.mk_empty_vertices(), |a, b| a.union(b));
+        assert_eq!(expected, found);
+        for component in classification.components() {
+            match component.kind() {
+                SccKind::Attractor => {
+                    assert!(stg.post(component.set()).is_subset(component.set()))
+                }
+                SccKind::Source => assert!(stg.pre(component.set()).is_subset(component.set())),
+                SccKind::Transient => {}
+            }
+            let vertices = component.set().minus(&component.set().pick_vertex());
+            match component.behaviour() {
+                SccBehaviour::Stable => assert!(vertices.is_empty()),
+                _ => assert_eq!(component.colors(), vertices.colors()),
+            }
+        }
+
+        // Attractor counts partition the color space.
+        let mut covered = stg.mk_empty_colors();
+        for i in 0..=classification.max_components() {
+            let colors = classification.colors_with_attractors(i);
+            assert_eq!(colors, first.colors_with_attractors(i));
+            assert!(colors.intersect(&covered).is_empty());
+            covered = covered.union(&colors);
+        }
+        assert_eq!(stg.mk_unit_colors(), covered);
+        assert!(classification.colors_with_attractors(0).is_empty());
+
+        // Partition classes are disjoint and agree with component counts.
+        let mut covered = stg.mk_empty_colors();
+        for (colors, members) in classification.partition() {
+            assert!(colors.intersect(&covered).is_empty());
+            covered = covered.union(&colors);
+            assert!(colors.is_subset(&classification.colors_with_components(members.len())));
+        }
+        assert_eq!(stg.mk_unit_colors(), covered);
+    }
+}
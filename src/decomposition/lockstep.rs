@@ -0,0 +1,393 @@
+//! The lockstep SCC decomposition algorithm.
+//!
+//! Similar to the basic forward-backward algorithm, lockstep picks a pivot vertex and computes
+//! its forward and backward reachable sets. However, both sets are computed simultaneously
+//! and only the set which converges first is completed, while the other one is only
+//! finished inside the converged set. Since the converged set is closed, it can be used
+//! to split the universe even if the other reachable set is not known.
+//!
+//! The algorithm can be configured using `LockstepOptions`:
+//!
+//!  - With `saturation`, each reachability step only uses the transitions of a single
+//!    variable (the first one, in reverse order, which adds new vertices), which typically
+//!    produces much smaller BDDs than the full `post`/`pre` step.
+//!  - With `color_splitting`, convergence is tracked separately for every color, so that
+//!    each color completes whichever reachable set converges first *in this color*. The universe
+//!    is then split into colors where the forward set converged first and colors where the
+//!    backward set converged first. Without it, the whole colored set advances the reachable
+//!    set with the smaller BDD and the first set which converges in all colors is completed.
+//!  - With `parallel`, independent sub-universes are decomposed in parallel.
+//!
+//! Additionally, the `cut_off` of the `DecompositionOptions` can be used to skip components
+//! that are too small (see `fraction_cut_off`).
+
+use crate::biodivine_std::traits::Set;
+use crate::budget::{InterruptReason, Interrupted};
+use crate::decomposition::{decompose, decompose_parallel, DecompositionOptions, Split};
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+
+/// Configuration of the lockstep SCC decomposition.
+///
+/// The `Default` configuration uses saturation with color splitting, runs sequentially
+/// and uses the default `DecompositionOptions`.
+#[derive(Clone)]
+pub struct LockstepOptions<'a> {
+    /// Common options of all decomposition algorithms (cut-off, observer, budget, ...).
+    pub decomposition: DecompositionOptions<'a>,
+    /// Use saturation (one variable at a time) instead of full `post`/`pre` steps.
+    pub saturation: bool,
+    /// Decompose independent sub-universes in parallel.
+    pub parallel: bool,
+    /// Track the convergence of the reachable sets separately for every color.
+    pub color_splitting: bool,
+}
+
+impl Default for LockstepOptions<'_> {
+    fn default() -> Self {
+        LockstepOptions {
+            decomposition: DecompositionOptions::default(),
+            saturation: true,
+            parallel: false,
+            color_splitting: true,
+        }
+    }
+}
+
+/// Decompose the whole state space of the `graph` into colored SCCs using the lockstep
+/// algorithm configured by the given `options`.
+///
+/// The `callback` is called for every component that satisfies the `decomposition` options
+/// (if `parallel` is set, it can be called from multiple threads). Each such component
+/// contains exactly one SCC for every one of its colors.
+///
+/// If the budget of the options is exhausted, the partial result of the error is the part
+/// of the state space that has not been decomposed yet.
+pub fn lockstep<F>(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    callback: F,
+) -> Result<(), Interrupted<GraphColoredVertices>>
+where
+    F: Fn(GraphColoredVertices) + Sync,
+{
+    let split = |universe| {
+        if options.color_splitting {
+            color_split(graph, options, universe)
+        } else {
+            symbolic_split(graph, options, universe)
+        }
+    };
+    if options.parallel {
+        decompose_parallel(graph, &options.decomposition, callback, split)
+    } else {
+        decompose(graph, &options.decomposition, callback, split)
+    }
+}
+
+/// Compute a cut-off which skips all components smaller than the square root of the
+/// state space of the `graph` (rounded to a power of two).
+///
+/// This is the cut-off used by the "fraction" variants of our benchmarks. Typically, it is
+/// used to quickly find the large components of a very big state space.
+pub fn fraction_cut_off(graph: &SymbolicAsyncGraph) -> f64 {
+    let magnitude = graph
+        .unit_colored_vertices()
+        .vertices()
+        .approx_cardinality()
+        .log2()
+        .ceil() as usize;
+    2f64.powi((magnitude / 2) as i32)
+}
+
+/// **(internal)** One lockstep iteration where the whole colored set advances together.
+fn symbolic_split(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    universe: GraphColoredVertices,
+) -> Result<Option<Split>, InterruptReason> {
+    let budget = &options.decomposition.budget;
+    let observer = options.decomposition.observer();
+    let universe = options
+        .decomposition
+        .trim(graph, universe)
+        .map_err(|e| e.reason)?;
+    if universe.is_empty() {
+        return Ok(None);
+    }
+
+    let pivot = universe.pick_vertex();
+    let colors = universe.colors();
+    let mut fwd = pivot.clone();
+    let mut bwd = pivot.clone();
+
+    // First, advance the symbolically smaller set until one of them converges.
+    let bwd_converged = loop {
+        budget.check(fwd.as_bdd())?;
+        budget.check(bwd.as_bdd())?;
+        if fwd.as_bdd().size() < bwd.as_bdd().size() {
+            observer.on_fwd_step(&fwd, &universe);
+            let step = fwd_step(graph, options, &fwd, &universe, &colors);
+            if step.is_empty() {
+                break false;
+            }
+            fwd = fwd.union(&step);
+        } else {
+            observer.on_bwd_step(&bwd, &universe);
+            let step = bwd_step(graph, options, &bwd, &universe, &colors);
+            if step.is_empty() {
+                break true;
+            }
+            bwd = bwd.union(&step);
+        }
+    };
+
+    // Then finish the other set inside the converged one.
+    if bwd_converged {
+        fwd = fwd_reach(graph, options, fwd, &bwd, &colors)?;
+    } else {
+        bwd = bwd_reach(graph, options, bwd, &fwd, &colors)?;
+    }
+
+    let scc = fwd.intersect(&bwd);
+    let converged = if bwd_converged { &bwd } else { &fwd };
+    let children = vec![universe.minus(converged), converged.minus(&scc)];
+    Ok(Some(Split {
+        pivot,
+        scc,
+        children,
+    }))
+}
+
+/// **(internal)** One lockstep iteration where the convergence is tracked for every color.
+fn color_split(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    universe: GraphColoredVertices,
+) -> Result<Option<Split>, InterruptReason> {
+    let budget = &options.decomposition.budget;
+    let observer = options.decomposition.observer();
+    let universe = options
+        .decomposition
+        .trim(graph, universe)
+        .map_err(|e| e.reason)?;
+    if universe.is_empty() {
+        return Ok(None);
+    }
+
+    let pivot = universe.pick_vertex();
+    let mut fwd = pivot.clone();
+    let mut bwd = pivot.clone();
+    let mut done_fwd = graph.mk_empty_colors();
+
+    // Advance both sets in every color until one of them converges.
+    let mut remaining = universe.colors();
+    while !remaining.is_empty() {
+        budget.check(fwd.as_bdd())?;
+        budget.check(bwd.as_bdd())?;
+
+        observer.on_fwd_step(&fwd, &universe);
+        let next_fwd = fwd_step(graph, options, &fwd, &universe, &remaining);
+        let next_fwd_colors = next_fwd.colors();
+        // Forward set converged for colors which were remaining but have no successors.
+        done_fwd = done_fwd.union(&remaining.minus(&next_fwd_colors));
+        remaining = remaining.intersect(&next_fwd_colors);
+        fwd = fwd.union(&next_fwd);
+
+        observer.on_bwd_step(&bwd, &universe);
+        let next_bwd = bwd_step(graph, options, &bwd, &universe, &remaining);
+        remaining = remaining.intersect(&next_bwd.colors());
+        bwd = bwd.union(&next_bwd);
+    }
+
+    // The backward set converged in all other colors.
+    let done_bwd = universe.colors().minus(&done_fwd);
+
+    // Finish the sets which did not converge inside the ones which did.
+    bwd = bwd_reach(graph, options, bwd, &fwd, &done_fwd)?;
+    fwd = fwd_reach(graph, options, fwd, &bwd, &done_bwd)?;
+
+    let scc = fwd.intersect(&bwd);
+    let converged = fwd
+        .intersect_colors(&done_fwd)
+        .union(&bwd.intersect_colors(&done_bwd));
+    let children = vec![universe.minus(&converged), converged.minus(&scc)];
+    Ok(Some(Split {
+        pivot,
+        scc,
+        children,
+    }))
+}
+
+/// **(internal)** Compute successors of `set` inside `universe` which are not in `set`, such
+/// that for every color of `colors` which has such successors, at least one is included.
+///
+/// Only the `colors` of the `set` are considered.
+fn fwd_step(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    set: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    colors: &GraphColors,
+) -> GraphColoredVertices {
+    if !options.saturation {
+        return graph
+            .post(&set.intersect_colors(colors))
+            .intersect(universe)
+            .minus(set);
+    }
+    let mut to_step = colors.clone();
+    let mut result = graph.mk_empty_vertices();
+    for var in graph.as_network().variables().rev() {
+        if to_step.is_empty() {
+            break;
+        }
+        let step = graph
+            .var_post(var, &set.intersect_colors(&to_step))
+            .intersect(universe)
+            .minus(set);
+        if !step.is_empty() {
+            to_step = to_step.minus(&step.colors());
+            result = result.union(&step);
+        }
+    }
+    result
+}
+
+/// **(internal)** Same as `fwd_step`, but computes predecessors.
+fn bwd_step(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    set: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    colors: &GraphColors,
+) -> GraphColoredVertices {
+    if !options.saturation {
+        return graph
+            .pre(&set.intersect_colors(colors))
+            .intersect(universe)
+            .minus(set);
+    }
+    let mut to_step = colors.clone();
+    let mut result = graph.mk_empty_vertices();
+    for var in graph.as_network().variables().rev() {
+        if to_step.is_empty() {
+            break;
+        }
+        let step = graph
+            .var_pre(var, &set.intersect_colors(&to_step))
+            .intersect(universe)
+            .minus(set);
+        if !step.is_empty() {
+            to_step = to_step.minus(&step.colors());
+            result = result.union(&step);
+        }
+    }
+    result
+}
+
+/// **(internal)** Extend `fwd` with all vertices of `universe` reachable from it in `colors`.
+fn fwd_reach(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    mut fwd: GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    colors: &GraphColors,
+) -> Result<GraphColoredVertices, InterruptReason> {
+    loop {
+        options.decomposition.budget.check(fwd.as_bdd())?;
+        options.decomposition.observer().on_fwd_step(&fwd, universe);
+        let step = fwd_step(graph, options, &fwd, universe, colors);
+        if step.is_empty() {
+            return Ok(fwd);
+        }
+        fwd = fwd.union(&step);
+    }
+}
+
+/// **(internal)** Extend `bwd` with all vertices of `universe` which can reach it in `colors`.
+fn bwd_reach(
+    graph: &SymbolicAsyncGraph,
+    options: &LockstepOptions,
+    mut bwd: GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    colors: &GraphColors,
+) -> Result<GraphColoredVertices, InterruptReason> {
+    loop {
+        options.decomposition.budget.check(bwd.as_bdd())?;
+        options.decomposition.observer().on_bwd_step(&bwd, universe);
+        let step = bwd_step(graph, options, &bwd, universe, colors);
+        if step.is_empty() {
+            return Ok(bwd);
+        }
+        bwd = bwd.union(&step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::traits::Set;
+    use crate::decomposition::lockstep::{fraction_cut_off, lockstep, LockstepOptions};
+    use crate::decomposition::{baseline_fwd_bwd, fwd_saturation, DecompositionOptions};
+    use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+    use std::sync::Mutex;
+
+    fn union(graph: &SymbolicAsyncGraph, sets: Vec<GraphColoredVertices>) -> GraphColoredVertices {
+        sets.iter()
+            .fold(graph.mk_empty_vertices(), |a, b| a.union(b))
+    }
+
+    #[test]
+    fn lockstep_variants_agree_with_fwd_bwd() {
+        let stg = SymbolicAsyncGraph::new(tutorial_network()).unwrap();
+
+        for report_trivial in &[false, true] {
+            let decomposition = DecompositionOptions {
+                report_trivial: *report_trivial,
+                ..DecompositionOptions::default()
+            };
+            let expected = Mutex::new(Vec::new());
+            baseline_fwd_bwd(&stg, &decomposition, |scc| {
+                expected.lock().unwrap().push(scc)
+            })
+            .unwrap();
+            let expected = union(&stg, expected.into_inner().unwrap());
+
+            for flags in 0..8 {
+                let options = LockstepOptions {
+                    decomposition: decomposition.clone(),
+                    saturation: flags & 1 != 0,
+                    parallel: flags & 2 != 0,
+                    color_splitting: flags & 4 != 0,
+                };
+                let components = Mutex::new(Vec::new());
+                lockstep(&stg, &options, |scc| components.lock().unwrap().push(scc)).unwrap();
+                let components = components.into_inner().unwrap();
+                for (i, scc) in components.iter().enumerate() {
+                    // Every component is strongly connected and disjoint with the others.
+                    let pivot = scc.pick_vertex();
+                    let fwd =
+                        fwd_saturation(&stg, scc, pivot, &DecompositionOptions::default()).unwrap();
+                    assert_eq!(scc, &fwd);
+                    for other in components.iter().skip(i + 1) {
+                        assert!(scc.intersect(other).is_empty());
+                    }
+                }
+                assert_eq!(expected, union(&stg, components));
+            }
+        }
+    }
+
+    #[test]
+    fn fraction_cut_off_of_large_state_space() {
+        let network = (0..130)
+            .map(|i| format!("x{} -> x{}", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let bn = BooleanNetwork::try_from(network.as_str()).unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        assert_eq!(2f64.powi(65), fraction_cut_off(&stg));
+    }
+}
@@ -0,0 +1,718 @@
+use crate::biodivine_std::traits::Set;
+use crate::budget::{Budget, InterruptReason, Interrupted};
+use crate::reachability::{saturation, SaturationOrder};
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub mod lockstep;
+
+/// **(internal)** Implementation of `SccClassification` and `ClassifiedScc`.
+mod _impl_scc_classification;
+
+/// Configuration of the SCC decomposition procedures (`baseline_fwd_bwd` and
+/// `baseline_fwd_bwd_parallel`).
+///
+/// The `Default` configuration reports every non-trivial component, does not cut off
+/// any part of the state space, does not observe the computation and is never interrupted.
+#[derive(Clone, Default)]
+pub struct DecompositionOptions<'a> {
+    /// Components and sub-universes with fewer vertices than `cut_off` are neither reported
+    /// nor explored further. The vertex count is taken across all colors of the set.
+    pub cut_off: f64,
+    /// If set, components consisting of a single (pivot) vertex are reported as well.
+    /// This also disables trimming, since it would discard such components silently.
+    pub report_trivial: bool,
+    /// An optional observer which receives progress information about the computation.
+    pub observer: Option<&'a dyn DecompositionObserver>,
+    /// Limits on the resources of the computation. The budget is checked after every
+    /// fixed-point step.
+    pub budget: Budget,
+}
+
+/// Receives progress information from the symbolic SCC algorithms in this module.
+///
+/// All methods have an empty default implementation, so an observer only needs to implement
+/// the events it is interested in. The observer can be called from multiple threads
+/// at the same time when used with parallel algorithms.
+pub trait DecompositionObserver: Sync {
+    /// Called for every step of a forward reachability procedure.
+    fn on_fwd_step(&self, _fwd: &GraphColoredVertices, _universe: &GraphColoredVertices) {}
+
+    /// Called for every step of a backward reachability procedure.
+    fn on_bwd_step(&self, _bwd: &GraphColoredVertices, _universe: &GraphColoredVertices) {}
+
+    /// Called for every step of the trimming procedure with the current (partially
+    /// trimmed) universe.
+    fn on_trim_step(&self, _universe: &GraphColoredVertices) {}
+
+    /// Called when a new component is discovered (before filtering based on the
+    /// options of the algorithm).
+    fn on_component(&self, _scc: &GraphColoredVertices) {}
+
+    /// Called after each iteration of the decomposition with the number of universes
+    /// that are waiting to be processed and their approximate total cardinality.
+    fn on_progress(&self, _universes: usize, _remaining: f64) {}
+}
+
+/// A no-op observer used when no observer is given.
+impl DecompositionObserver for () {}
+
+/// Position of a (colored) SCC within the graph of SCCs of a state-transition graph.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SccKind {
+    /// A terminal (sink) component with no outgoing transitions, i.e. an attractor.
+    Attractor,
+    /// A non-terminal component with no incoming transitions.
+    Source,
+    /// A component with both incoming and outgoing transitions.
+    Transient,
+}
+
+/// Behaviour of the network inside a (colored) SCC.
+///
+/// For attractors, see `attractor_behaviour` for the exact definition. Other components
+/// are only classified as `Stable` or `Oscillating` based on the number of their vertices.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SccBehaviour {
+    /// The component is a single vertex (for attractors, this is a fixed point).
+    Stable,
+    /// The component contains more than one vertex. In attractors, every vertex has
+    /// exactly one successor, i.e. the attractor is a simple cycle.
+    Oscillating,
+    /// An attractor where some vertex has more than one successor.
+    Disorder,
+}
+
+/// A colored SCC together with its classification. The classification is the same
+/// for all colors of the component.
+#[derive(Clone)]
+pub struct ClassifiedScc {
+    set: GraphColoredVertices,
+    kind: SccKind,
+    behaviour: SccBehaviour,
+}
+
+/// A collection of classified SCCs of a `SymbolicAsyncGraph`, typically produced
+/// by one of the decomposition algorithms.
+///
+/// Every pushed component is split into parts with uniform classification. The result can
+/// be then used to query colors based on the number and type of their components, or to
+/// partition the colors based on which components they admit.
+#[derive(Clone)]
+pub struct SccClassification<'a> {
+    graph: &'a SymbolicAsyncGraph,
+    components: Vec<ClassifiedScc>,
+}
+
+/// **(internal)** Result of an interruptible fixed-point computation. The partial result
+/// is the last set computed before the interruption.
+type FixedPoint = Result<GraphColoredVertices, Interrupted<GraphColoredVertices>>;
+
+impl DecompositionOptions<'_> {
+    /// **(internal)** The observer of these options, or a no-op observer if not set.
+    fn observer(&self) -> &dyn DecompositionObserver {
+        self.observer.unwrap_or(&())
+    }
+
+    /// **(internal)** Check the budget of these options, assuming `set` is the latest result
+    /// of a fixed-point computation.
+    fn check(&self, set: &GraphColoredVertices) -> Result<(), Interrupted<GraphColoredVertices>> {
+        self.budget
+            .check(set.as_bdd())
+            .map_err(|reason| Interrupted::new(reason, set.clone()))
+    }
+
+    /// **(internal)** Restrict the `scc` discovered from the given `pivot` to colors in which
+    /// it should be reported (i.e. the component is not trivial and not too small).
+    ///
+    /// Returns `None` if the component should not be reported at all.
+    fn reported_component(
+        &self,
+        scc: &GraphColoredVertices,
+        pivot: &GraphColoredVertices,
+    ) -> Option<GraphColoredVertices> {
+        let scc = if self.report_trivial {
+            scc.clone()
+        } else {
+            scc.intersect_colors(&scc.minus(pivot).colors())
+        };
+        if scc.is_empty() || scc.vertices().approx_cardinality() < self.cut_off {
+            None
+        } else {
+            Some(scc)
+        }
+    }
+
+    /// **(internal)** Remove vertices which cannot belong to a non-trivial SCC from the
+    /// `universe`, unless trivial components should be reported as well.
+    fn trim(&self, graph: &SymbolicAsyncGraph, universe: GraphColoredVertices) -> FixedPoint {
+        if self.report_trivial {
+            Ok(universe)
+        } else {
+            trim(graph, universe, self)
+        }
+    }
+
+    /// **(internal)** True if the given sub-universe should be explored further.
+    fn should_explore(&self, universe: &GraphColoredVertices) -> bool {
+        !universe.is_empty() && universe.vertices().approx_cardinality() >= self.cut_off
+    }
+}
+
+/// Decompose the whole state space of the `graph` into colored SCCs, exploring the
+/// independent parts of the state space in parallel.
+///
+/// The `callback` is called (possibly from multiple threads) for every component that
+/// satisfies the given `options`. Each such component contains exactly one SCC for every
+/// one of its colors.
+///
+/// If the budget of the `options` is exhausted, all threads stop and the partial result
+/// of the error is the part of the state space that has not been decomposed yet.
+pub fn baseline_fwd_bwd_parallel<F>(
+    graph: &SymbolicAsyncGraph,
+    options: &DecompositionOptions,
+    callback: F,
+) -> Result<(), Interrupted<GraphColoredVertices>>
+where
+    F: Fn(GraphColoredVertices) + Sync,
+{
+    decompose_parallel(graph, options, callback, |universe| {
+        fwd_bwd_split(graph, options, universe)
+    })
+}
+
+/// Decompose the whole state space of the `graph` into colored SCCs.
+///
+/// The `callback` is called for every component that satisfies the given `options`. Each
+/// such component contains exactly one SCC for every one of its colors.
+///
+/// If the budget of the `options` is exhausted, the partial result of the error is the part
+/// of the state space that has not been decomposed yet.
+pub fn baseline_fwd_bwd<F>(
+    graph: &SymbolicAsyncGraph,
+    options: &DecompositionOptions,
+    callback: F,
+) -> Result<(), Interrupted<GraphColoredVertices>>
+where
+    F: Fn(GraphColoredVertices),
+{
+    decompose(graph, options, callback, |universe| {
+        fwd_bwd_split(graph, options, universe)
+    })
+}
+
+/// **(internal)** Result of one iteration of a divide-and-conquer SCC decomposition:
+/// the component of the `pivot` and the sub-universes which still need to be decomposed.
+struct Split {
+    pivot: GraphColoredVertices,
+    scc: GraphColoredVertices,
+    children: Vec<GraphColoredVertices>,
+}
+
+/// **(internal)** One iteration of the basic forward-backward algorithm.
+///
+/// Returns `None` if there is no non-trivial component in the `universe`.
+fn fwd_bwd_split(
+    graph: &SymbolicAsyncGraph,
+    options: &DecompositionOptions,
+    universe: GraphColoredVertices,
+) -> Result<Option<Split>, InterruptReason> {
+    let universe = options.trim(graph, universe).map_err(|e| e.reason)?;
+    if universe.is_empty() {
+        return Ok(None);
+    }
+    let pivot = universe.pick_vertex();
+    let fwd = fwd_saturation(graph, &universe, pivot.clone(), options).map_err(|e| e.reason)?;
+    let bwd = bwd_saturation(graph, &universe, pivot.clone(), options).map_err(|e| e.reason)?;
+    let scc = fwd.intersect(&bwd);
+    let below = fwd.minus(&scc);
+    let above = bwd.minus(&scc);
+    let rest = universe.minus(&fwd.union(&bwd));
+    Ok(Some(Split {
+        pivot,
+        scc,
+        children: vec![below, above, rest],
+    }))
+}
+
+/// **(internal)** Decompose the whole state space of the `graph` by repeatedly applying
+/// the `split` function to the universes which still need to be explored.
+fn decompose<F, S>(
+    graph: &SymbolicAsyncGraph,
+    options: &DecompositionOptions,
+    callback: F,
+    split: S,
+) -> Result<(), Interrupted<GraphColoredVertices>>
+where
+    F: Fn(GraphColoredVertices),
+    S: Fn(GraphColoredVertices) -> Result<Option<Split>, InterruptReason>,
+{
+    let observer = options.observer();
+    let mut universes = vec![graph.mk_unit_colored_vertices()];
+    universes.retain(|it| options.should_explore(it));
+
+    while let Some(universe) = universes.pop() {
+        let parts = match split(universe.clone()) {
+            Ok(None) => continue,
+            Ok(Some(parts)) => parts,
+            Err(reason) => {
+                let remaining = universes.iter().fold(universe, |a, b| a.union(b));
+                return Err(Interrupted::new(reason, remaining));
+            }
+        };
+
+        observer.on_component(&parts.scc);
+        for child in parts.children {
+            if options.should_explore(&child) {
+                universes.push(child);
+            }
+        }
+
+        if let Some(reported) = options.reported_component(&parts.scc, &parts.pivot) {
+            callback(reported);
+        }
+
+        let remaining: f64 = universes.iter().map(|it| it.approx_cardinality()).sum();
+        observer.on_progress(universes.len(), remaining);
+    }
+
+    Ok(())
+}
+
+/// **(internal)** Same as `decompose`, but independent universes are explored in parallel.
+fn decompose_parallel<F, S>(
+    graph: &SymbolicAsyncGraph,
+    options: &DecompositionOptions,
+    callback: F,
+    split: S,
+) -> Result<(), Interrupted<GraphColoredVertices>>
+where
+    F: Fn(GraphColoredVertices) + Sync,
+    S: Fn(GraphColoredVertices) -> Result<Option<Split>, InterruptReason> + Sync,
+{
+    let interrupted = Mutex::new(None);
+    let universe = graph.mk_unit_colored_vertices();
+    if options.should_explore(&universe) {
+        parallel_recursion(options, &callback, &split, &interrupted, universe);
+    }
+    match interrupted.into_inner().unwrap() {
+        None => Ok(()),
+        Some(error) => Err(error),
+    }
+}
+
+/// **(internal)** Split the `universe` and recursively explore its children in parallel.
+///
+/// Once interrupted (in any thread), the remaining universes are collected in `interrupted`.
+fn parallel_recursion<F, S>(
+    options: &DecompositionOptions,
+    callback: &F,
+    split: &S,
+    interrupted: &Mutex<Option<Interrupted<GraphColoredVertices>>>,
+    universe: GraphColoredVertices,
+) where
+    F: Fn(GraphColoredVertices) + Sync,
+    S: Fn(GraphColoredVertices) -> Result<Option<Split>, InterruptReason> + Sync,
+{
+    // Record the unprocessed universe and stop if the computation was interrupted
+    // (either here or in some other thread).
+    let interrupt = |reason: Option<InterruptReason>, universe: &GraphColoredVertices| {
+        let mut interrupted = interrupted.lock().unwrap();
+        if let Some(error) = interrupted.as_mut() {
+            error.partial = error.partial.union(universe);
+        } else if let Some(reason) = reason {
+            *interrupted = Some(Interrupted::new(reason, universe.clone()));
+        }
+    };
+    if interrupted.lock().unwrap().is_some() {
+        interrupt(None, &universe);
+        return;
+    }
+
+    let parts = match split(universe.clone()) {
+        Ok(None) => return,
+        Ok(Some(parts)) => parts,
+        Err(reason) => {
+            interrupt(Some(reason), &universe);
+            return;
+        }
+    };
+
+    options.observer().on_component(&parts.scc);
+    if let Some(reported) = options.reported_component(&parts.scc, &parts.pivot) {
+        callback(reported);
+    }
+
+    rayon::scope(|s| {
+        for child in parts.children {
+            if options.should_explore(&child) {
+                s.spawn(move |_| parallel_recursion(options, callback, split, interrupted, child));
+            }
+        }
+    });
+}
+
+/// Compute all colored attractors (terminal SCCs) of the given `graph` restricted
+/// to the `universe` set.
+///
+/// Every item of the result is a colored set of vertices which contains, for each of its
+/// colors, exactly one attractor of the subgraph induced by `universe` (if `universe` is not
+/// closed under `post`, transitions leaving the `universe` are ignored). One color can
+/// appear in several items if it admits multiple attractors.
+///
+/// The search repeatedly picks a pivot vertex for every color of the `universe`, computes
+/// its backward reachable set (basin) and the forward reachable set inside this basin,
+/// which is exactly the SCC of the pivot. The SCC is an attractor for all colors where it
+/// has no successors outside of itself. The basin of the pivot cannot contain any other
+/// attractor, so it is then removed from the `universe`.
+pub fn attractors(
+    graph: &SymbolicAsyncGraph,
+    universe: &GraphColoredVertices,
+) -> Vec<GraphColoredVertices> {
+    attractors_with_budget(graph, universe, &Budget::unlimited())
+        .expect("Unlimited budget cannot be exhausted.")
+}
+
+/// Same as `attractors`, but the computation stops once the given `budget` is exhausted.
+///
+/// The partial result of the error contains the attractors found before the interruption.
+pub fn attractors_with_budget(
+    graph: &SymbolicAsyncGraph,
+    universe: &GraphColoredVertices,
+    budget: &Budget,
+) -> Result<Vec<GraphColoredVertices>, Interrupted<Vec<GraphColoredVertices>>> {
+    let options = DecompositionOptions {
+        budget: budget.clone(),
+        ..DecompositionOptions::default()
+    };
+    let mut result = Vec::new();
+    let mut universe = universe.clone();
+    while !universe.is_empty() {
+        let pivot = universe.pick_vertex();
+        let basin = match bwd_saturation(graph, &universe, pivot.clone(), &options) {
+            Ok(basin) => basin,
+            Err(error) => return Err(Interrupted::new(error.reason, result)),
+        };
+        let component = match fwd_saturation(graph, &basin, pivot, &options) {
+            Ok(component) => component,
+            Err(error) => return Err(Interrupted::new(error.reason, result)),
+        };
+
+        // Colors in which the component can leave itself are not terminal.
+        let escaping_colors = graph
+            .post(&component)
+            .intersect(&universe)
+            .minus(&component)
+            .colors();
+        let attractor = component.minus_colors(&escaping_colors);
+        if !attractor.is_empty() {
+            result.push(attractor);
+        }
+
+        universe = universe.minus(&basin);
+    }
+    Ok(result)
+}
+
+/// Partition the colors of the given `attractor` based on the behaviour of the network
+/// inside the attractor. Only non-empty classes are included in the result.
+///
+///  - `Stable`: No vertex of the attractor has a successor, i.e. the attractor is a fixed point.
+///  - `Oscillating`: Every vertex of the attractor has exactly one successor, i.e. the
+///    attractor is a simple cycle.
+///  - `Disorder`: Some vertex of the attractor has more than one successor (or the attractor
+///    mixes vertices with and without successors).
+///
+/// The `attractor` should be closed under `post` in all its colors, otherwise transitions
+/// leaving the set are considered as well.
+pub fn attractor_behaviour(
+    graph: &SymbolicAsyncGraph,
+    attractor: &GraphColoredVertices,
+) -> HashMap<SccBehaviour, GraphColors> {
+    let colors = attractor.colors();
+    // Vertices with zero, exactly one, or more successors among the variables seen so far.
+    let mut zero = attractor.clone();
+    let mut one = graph.mk_empty_vertices();
+    let mut more = graph.mk_empty_vertices();
+    for var in graph.as_network().variables() {
+        let var_moves = graph.var_can_post(var, attractor);
+        more = more.union(&one.intersect(&var_moves));
+        one = one.minus(&var_moves).union(&zero.intersect(&var_moves));
+        zero = zero.minus(&var_moves);
+    }
+
+    let stable = colors.minus(&one.union(&more).colors());
+    let oscillating = one.colors().minus(&zero.union(&more).colors());
+    let disorder = colors.minus(&stable).minus(&oscillating);
+
+    let mut result = HashMap::new();
+    let classes = vec![
+        (SccBehaviour::Stable, stable),
+        (SccBehaviour::Oscillating, oscillating),
+        (SccBehaviour::Disorder, disorder),
+    ];
+    for (behaviour, colors) in classes {
+        if !colors.is_empty() {
+            result.insert(behaviour, colors);
+        }
+    }
+    result
+}
+
+/// **(internal)** Forward reachable set of `fwd` inside the `universe`, computed using saturation.
+fn fwd_saturation(
+    graph: &SymbolicAsyncGraph,
+    universe: &GraphColoredVertices,
+    fwd: GraphColoredVertices,
+    options: &DecompositionOptions,
+) -> FixedPoint {
+    let variables = SaturationOrder::default().variables(graph.as_network());
+    saturation(graph, fwd, universe, &variables, true, |set| {
+        options.observer().on_fwd_step(set, universe);
+        options.check(set)
+    })
+}
+
+/// **(internal)** Backward reachable set of `bwd` inside the `universe`, computed using saturation.
+fn bwd_saturation(
+    graph: &SymbolicAsyncGraph,
+    universe: &GraphColoredVertices,
+    bwd: GraphColoredVertices,
+    options: &DecompositionOptions,
+) -> FixedPoint {
+    let variables = SaturationOrder::default().variables(graph.as_network());
+    saturation(graph, bwd, universe, &variables, false, |set| {
+        options.observer().on_bwd_step(set, universe);
+        options.check(set)
+    })
+}
+
+/// Remove vertices which have no predecessor or no successor in the `universe`
+/// (and hence cannot belong to a non-trivial SCC).
+///
+/// On interruption, the partial result is a superset of the trimmed universe.
+fn trim(
+    graph: &SymbolicAsyncGraph,
+    mut universe: GraphColoredVertices,
+    options: &DecompositionOptions,
+) -> FixedPoint {
+    loop {
+        let can_go_fwd = graph
+            .pre(&graph.post(&universe).intersect(&universe))
+            .intersect(&universe);
+
+        let can_go_bwd = graph
+            .post(&graph.pre(&universe).intersect(&universe))
+            .intersect(&universe);
+        let can_step = can_go_fwd.intersect(&can_go_bwd);
+        options.observer().on_trim_step(&universe);
+        options.check(&universe)?;
+        if universe.is_subset(&can_step) {
+            // universe == can_step
+            return Ok(can_step);
+        }
+        universe = can_step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::traits::Set;
+    use crate::budget::{Budget, CancellationToken, InterruptReason};
+    use crate::decomposition::{
+        attractor_behaviour, attractors, attractors_with_budget, baseline_fwd_bwd,
+        baseline_fwd_bwd_parallel, DecompositionObserver, DecompositionOptions, SccBehaviour,
+    };
+    use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn tutorial_graph() -> SymbolicAsyncGraph {
+        SymbolicAsyncGraph::new(tutorial_network()).unwrap()
+    }
+
+    struct ComponentCounter(AtomicUsize);
+
+    impl DecompositionObserver for ComponentCounter {
+        fn on_component(&self, _scc: &GraphColoredVertices) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn decomposition_reports_all_components() {
+        let stg = tutorial_graph();
+        let options = DecompositionOptions {
+            report_trivial: true,
+            ..DecompositionOptions::default()
+        };
+        let components = Mutex::new(Vec::new());
+        baseline_fwd_bwd(&stg, &options, |scc| components.lock().unwrap().push(scc)).unwrap();
+        let components = components.into_inner().unwrap();
+        // Components are disjoint and cover the whole state space.
+        let mut covered = stg.mk_empty_vertices();
+        for scc in &components {
+            assert!(scc.intersect(&covered).is_empty());
+            covered = covered.union(scc);
+        }
+        assert_eq!(stg.mk_unit_colored_vertices(), covered);
+    }
+
+    #[test]
+    fn decomposition_reports_non_trivial_components() {
+        let stg = tutorial_graph();
+        let observer = ComponentCounter(AtomicUsize::new(0));
+        let options = DecompositionOptions {
+            observer: Some(&observer),
+            ..DecompositionOptions::default()
+        };
+        let sequential = Mutex::new(Vec::new());
+        baseline_fwd_bwd(&stg, &options, |scc| sequential.lock().unwrap().push(scc)).unwrap();
+        let sequential = sequential.into_inner().unwrap();
+        assert!(!sequential.is_empty());
+        assert!(observer.0.load(Ordering::SeqCst) >= sequential.len());
+        for scc in &sequential {
+            // Every reported component has more than one vertex in every color.
+            assert_eq!(scc.colors(), scc.minus(&scc.pick_vertex()).colors());
+        }
+
+        let parallel = Mutex::new(Vec::new());
+        baseline_fwd_bwd_parallel(&stg, &options, |scc| parallel.lock().unwrap().push(scc))
+            .unwrap();
+        let parallel = parallel.into_inner().unwrap();
+        let union = |sets: &Vec<GraphColoredVertices>| {
+            sets.iter().fold(stg.mk_empty_vertices(), |a, b| a.union(b))
+        };
+        assert_eq!(union(&sequential), union(&parallel));
+
+        // With a large cut-off, nothing is reported.
+        let options = DecompositionOptions {
+            cut_off: 100.0,
+            ..DecompositionOptions::default()
+        };
+        baseline_fwd_bwd(&stg, &options, |_| panic!("Component reported.")).unwrap();
+    }
+
+    #[test]
+    fn attractors_of_fixed_network() {
+        // Two fixed points (a=1,b=0 and a=0,b=1).
+        let bn = BooleanNetwork::try_from("a -| b \n b -| a \n $a: !b \n $b: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let result = attractors(&stg, stg.unit_colored_vertices());
+        assert_eq!(2, result.len());
+        for attractor in &result {
+            assert_eq!(1.0, attractor.vertices().approx_cardinality());
+        }
+
+        // One oscillating attractor with two states.
+        let bn = BooleanNetwork::try_from("a -| a \n $a: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let result = attractors(&stg, stg.unit_colored_vertices());
+        assert_eq!(1, result.len());
+        assert_eq!(2.0, result[0].vertices().approx_cardinality());
+    }
+
+    #[test]
+    fn attractors_of_parametrised_network() {
+        let stg = tutorial_graph();
+        let result = attractors(&stg, stg.unit_colored_vertices());
+        assert!(!result.is_empty());
+        let mut covered = stg.mk_empty_colors();
+        for (i, attractor) in result.iter().enumerate() {
+            // Every attractor is closed under post and disjoint with the others.
+            assert!(stg.post(attractor).is_subset(attractor));
+            for other in result.iter().skip(i + 1) {
+                assert!(attractor.intersect(other).is_empty());
+            }
+            covered = covered.union(&attractor.colors());
+        }
+        // Every color has at least one attractor.
+        assert_eq!(stg.mk_unit_colors(), covered);
+    }
+
+    #[test]
+    fn interrupted_decomposition() {
+        let stg = tutorial_graph();
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = DecompositionOptions {
+            budget: Budget::unlimited().with_cancellation(token),
+            ..DecompositionOptions::default()
+        };
+        let error = baseline_fwd_bwd(&stg, &options, |_| panic!("Component reported."));
+        let error = error.unwrap_err();
+        assert_eq!(InterruptReason::Cancelled, error.reason);
+        // Nothing has been decomposed, so the whole state space remains.
+        assert_eq!(stg.mk_unit_colored_vertices(), error.partial);
+
+        let error = baseline_fwd_bwd_parallel(&stg, &options, |_| panic!("Component reported."));
+        assert_eq!(stg.mk_unit_colored_vertices(), error.unwrap_err().partial);
+
+        let budget = Budget::unlimited().with_max_bdd_size(1);
+        let error = attractors_with_budget(&stg, stg.unit_colored_vertices(), &budget);
+        let error = error.unwrap_err();
+        assert_eq!(InterruptReason::BddSizeExceeded, error.reason);
+        assert!(error.partial.is_empty());
+    }
+
+    #[test]
+    fn attractor_behaviour_classes() {
+        // Fixed point (a=1) for f=true, otherwise a two-state cycle in a.
+        let bn = BooleanNetwork::try_from("a -| a \n $a: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let attractor = &attractors(&stg, stg.unit_colored_vertices())[0];
+        let classes = attractor_behaviour(&stg, attractor);
+        assert_eq!(1, classes.len());
+        assert_eq!(stg.mk_unit_colors(), classes[&SccBehaviour::Oscillating]);
+
+        let bn = BooleanNetwork::try_from("a -| b \n b -| a \n $a: !b \n $b: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        for attractor in attractors(&stg, stg.unit_colored_vertices()) {
+            let classes = attractor_behaviour(&stg, &attractor);
+            assert_eq!(1, classes.len());
+            assert_eq!(stg.mk_unit_colors(), classes[&SccBehaviour::Stable]);
+        }
+
+        // The whole state space is one simple cycle `00 -> 10 -> 11 -> 01 -> 00`.
+        let bn = BooleanNetwork::try_from("a -> b \n b -| a \n $a: !b \n $b: a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let result = attractors(&stg, stg.unit_colored_vertices());
+        assert_eq!(1, result.len());
+        let classes = attractor_behaviour(&stg, &result[0]);
+        assert_eq!(1, classes.len());
+        assert_eq!(stg.mk_unit_colors(), classes[&SccBehaviour::Oscillating]);
+
+        // Both variables flip independently, hence every vertex has two successors.
+        let bn = BooleanNetwork::try_from("a -| a \n b -| b \n $a: !a \n $b: !b").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let result = attractors(&stg, stg.unit_colored_vertices());
+        assert_eq!(1, result.len());
+        let classes = attractor_behaviour(&stg, &result[0]);
+        assert_eq!(1, classes.len());
+        assert_eq!(stg.mk_unit_colors(), classes[&SccBehaviour::Disorder]);
+
+        // Parametrised network: for `f(b) = b`, only `a` oscillates, for `f(b) = !b`,
+        // both variables flip.
+        let bn = BooleanNetwork::try_from("a -? a \n b -? b \n $a: !a \n $b: f(b)").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let mut oscillating = stg.mk_empty_colors();
+        let mut disorder = stg.mk_empty_colors();
+        for attractor in attractors(&stg, stg.unit_colored_vertices()) {
+            let classes = attractor_behaviour(&stg, &attractor);
+            assert!(!classes.contains_key(&SccBehaviour::Stable));
+            if let Some(colors) = classes.get(&SccBehaviour::Oscillating) {
+                oscillating = oscillating.union(colors);
+            }
+            if let Some(colors) = classes.get(&SccBehaviour::Disorder) {
+                disorder = disorder.union(colors);
+            }
+        }
+        assert_eq!(1.0, oscillating.approx_cardinality());
+        assert_eq!(1.0, disorder.approx_cardinality());
+        assert_eq!(stg.mk_unit_colors(), oscillating.union(&disorder));
+    }
+}
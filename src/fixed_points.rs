@@ -0,0 +1,210 @@
+//! Symbolic computation of fixed points (steady states) of parametrised Boolean networks.
+//!
+//! A vertex is a fixed point for a color if the update function of every variable evaluates
+//! to the current value of that variable. Instead of computing `!can_post` over the whole
+//! state space, we build the constraint `x_i <=> f_i` for every variable using the
+//! `SymbolicContext` and conjoin these constraints directly.
+//!
+//! The order of conjunctions is important: every constraint only depends on a few
+//! variables, but a naive left-to-right conjunction quickly produces BDDs which depend
+//! on all of them. Here, we always merge the two smallest BDDs first (similar to Huffman
+//! coding), which keeps the intermediate results small for typical networks where the
+//! constraints of related variables can be merged without a blow-up.
+
+use crate::biodivine_std::traits::Set;
+use crate::budget::{Budget, Interrupted};
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use biodivine_lib_bdd::Bdd;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Compute all colored fixed points of the given `graph`.
+pub fn fixed_points(graph: &SymbolicAsyncGraph) -> GraphColoredVertices {
+    fixed_points_in(graph, graph.unit_colored_vertices())
+}
+
+/// Compute all colored fixed points of the given `graph` which belong to the `restriction`.
+///
+/// The result only contains vertices of the `graph`, even if the `restriction` does not.
+pub fn fixed_points_in(
+    graph: &SymbolicAsyncGraph,
+    restriction: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    fixed_points_with_budget(graph, restriction, &Budget::unlimited())
+        .expect("Unlimited budget cannot be exhausted.")
+}
+
+/// Compute the colors of the given `graph` which admit at least one fixed point.
+pub fn fixed_point_colors(graph: &SymbolicAsyncGraph) -> GraphColors {
+    fixed_points(graph).colors()
+}
+
+/// Same as `fixed_points_in`, but the computation stops once the given `budget` is exhausted.
+///
+/// The partial result of the error is a superset of the colored fixed points in the
+/// `restriction`, specifically the most recently merged conjunction of constraints
+/// intersected with the `restriction` and the vertices of the `graph`.
+pub fn fixed_points_with_budget(
+    graph: &SymbolicAsyncGraph,
+    restriction: &GraphColoredVertices,
+    budget: &Budget,
+) -> Result<GraphColoredVertices, Interrupted<GraphColoredVertices>> {
+    let context = graph.symbolic_context();
+    let network = graph.as_network();
+    let mut constraints: Vec<Bdd> = network
+        .variables()
+        .map(|variable| {
+            let function_is_true = match network.get_update_function(variable) {
+                Some(function) => context.mk_fn_update_true(function),
+                None => {
+                    let regulators = network.regulators(variable);
+                    context.mk_implicit_function_is_true(variable, &regulators)
+                }
+            };
+            context
+                .mk_state_variable_is_true(variable)
+                .iff(&function_is_true)
+        })
+        .collect();
+    constraints.push(restriction.as_bdd().clone());
+
+    // Every merged constraint is a superset of the result, so it can serve as a partial result.
+    let mut latest = restriction.as_bdd().clone();
+    let mut queue: BinaryHeap<Item> = constraints
+        .into_iter()
+        .map(|bdd| Item(Reverse(bdd.size()), bdd))
+        .collect();
+    while queue.len() > 1 {
+        if let Err(reason) = budget.check(&latest) {
+            let partial = GraphColoredVertices::new(latest, context)
+                .intersect(restriction)
+                .intersect(graph.unit_colored_vertices());
+            return Err(Interrupted::new(reason, partial));
+        }
+        let Item(_, first) = queue.pop().unwrap();
+        let Item(_, second) = queue.pop().unwrap();
+        latest = first.and(&second);
+        if latest.is_false() {
+            return Ok(graph.mk_empty_vertices());
+        }
+        queue.push(Item(Reverse(latest.size()), latest.clone()));
+    }
+
+    let result = queue.pop().map(|it| it.1).unwrap();
+    Ok(GraphColoredVertices::new(result, context).intersect(graph.unit_colored_vertices()))
+}
+
+/// **(internal)** A `Bdd` ordered by its size (smallest first in a `BinaryHeap`).
+struct Item(Reverse<usize>, Bdd);
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Item {}
+
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::budget::{Budget, InterruptReason};
+    use crate::fixed_points::{
+        fixed_point_colors, fixed_points, fixed_points_in, fixed_points_with_budget,
+    };
+    use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn fixed_points_agree_with_can_post() {
+        // Depending on the color, the network has zero, one or two fixed points.
+        let bn = BooleanNetwork::try_from("a -?? b \n b -?? a \n a -?? a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let mut expected = stg.mk_unit_colored_vertices();
+        for var in stg.as_network().variables() {
+            expected = expected.minus(&stg.var_can_post(var, stg.unit_colored_vertices()));
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(expected, fixed_points(&stg));
+        assert_eq!(expected.colors(), fixed_point_colors(&stg));
+
+        let budget = Budget::unlimited().with_max_bdd_size(1);
+        let error = fixed_points_with_budget(&stg, stg.unit_colored_vertices(), &budget);
+        let error = error.unwrap_err();
+        assert_eq!(InterruptReason::BddSizeExceeded, error.reason);
+        assert!(expected.is_subset(&error.partial));
+    }
+
+    #[test]
+    fn interrupted_fixed_points_stay_in_restriction() {
+        // Four independent copies of `a <=> b xor c`, with `b` and `c` being constant.
+        let mut network = Vec::new();
+        for i in 0..4 {
+            network.push(format!("b{i} -? a{i} \n c{i} -? a{i}", i = i));
+            network.push(format!("b{i} -> b{i} \n c{i} -> c{i}", i = i));
+            network.push(format!(
+                "$a{i}: b{i} ^ c{i} \n $b{i}: b{i} \n $c{i}: c{i}",
+                i = i
+            ));
+        }
+        let bn = BooleanNetwork::try_from(network.join("\n").as_str()).unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        // Restrict to vertices where an even number of `b` and `c` variables is true.
+        let mut even = stg.mk_unit_colored_vertices();
+        let mut odd = stg.mk_empty_vertices();
+        for var in stg.as_network().variables() {
+            let name = stg.as_network().get_variable_name(var);
+            if !name.starts_with('a') {
+                let is_true = stg.fix_network_variable(var, true);
+                let is_false = stg.fix_network_variable(var, false);
+                let next_even = even.intersect(&is_false).union(&odd.intersect(&is_true));
+                odd = odd.intersect(&is_false).union(&even.intersect(&is_true));
+                even = next_even;
+            }
+        }
+        let expected = fixed_points(&stg).intersect(&even);
+        let mut interrupted = 0;
+        for max_size in 1..100 {
+            let budget = Budget::unlimited().with_max_bdd_size(max_size);
+            if let Err(error) = fixed_points_with_budget(&stg, &even, &budget) {
+                interrupted += 1;
+                assert!(error.partial.is_subset(&even));
+                assert!(error.partial.is_subset(stg.unit_colored_vertices()));
+                assert!(expected.is_subset(&error.partial));
+            }
+        }
+        assert!(interrupted > 0);
+    }
+
+    #[test]
+    fn fixed_points_satisfy_static_constraints() {
+        // `f(b) = !b` violates the monotonicity of `b -> a`, but it admits fixed points.
+        let bn = BooleanNetwork::try_from("b ->? a \n b -> b \n $b: b").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let context = stg.symbolic_context();
+        let everything = GraphColoredVertices::new(context.mk_constant(true), context);
+        let result = fixed_points_in(&stg, &everything);
+        assert!(result.is_subset(stg.unit_colored_vertices()));
+        assert_eq!(fixed_points(&stg), result);
+    }
+
+    #[test]
+    fn fixed_points_of_cycle() {
+        let bn = BooleanNetwork::try_from("a -| a \n $a: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        assert!(fixed_points(&stg).is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+//! Inference of parametrisations (colors) which are consistent with experimental data.
+//!
+//! The data are given as partial observations of network states (values of some variables
+//! can be unknown). A `Dataset` is an ordered collection of such observations and can be
+//! interpreted as a set of observed fixed points, a set of observed attractor states, or as
+//! a time series, where every observation must be reachable from the previous one.
+
+use crate::biodivine_std::traits::Set;
+use crate::decomposition::attractors;
+use crate::fixed_points::fixed_points;
+use crate::reachability::{reach_bwd, reach_fwd};
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use crate::VariableId;
+use std::convert::TryFrom;
+
+/// A partial observation of a network state: every variable is either observed
+/// with a value, or unknown (`None`).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Observation {
+    values: Vec<Option<bool>>,
+}
+
+/// An ordered collection of observations over the same set of variables.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Dataset {
+    observations: Vec<Observation>,
+}
+
+impl Observation {
+    /// Create a new observation with the given value (or `None` when unknown) for
+    /// every network variable.
+    pub fn new(values: Vec<Option<bool>>) -> Observation {
+        Observation { values }
+    }
+
+    /// Create a new observation of `num_vars` variables where only the given variables
+    /// are observed.
+    ///
+    /// Panic: All variables must be smaller than `num_vars`.
+    pub fn from_values(num_vars: usize, values: &[(VariableId, bool)]) -> Observation {
+        let mut result = vec![None; num_vars];
+        for (var, value) in values {
+            result[var.0] = Some(*value);
+        }
+        Observation { values: result }
+    }
+
+    /// The number of variables of this observation.
+    pub fn num_vars(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The observed value of the given `variable`, or `None` if the value is unknown.
+    pub fn get_value(&self, variable: VariableId) -> Option<bool> {
+        self.values[variable.0]
+    }
+
+    /// Create the set of all vertices of the `graph` (in all colors) which match
+    /// this observation.
+    ///
+    /// Returns an error if the observation and the network have a different number
+    /// of variables.
+    pub fn to_colored_vertices(
+        &self,
+        graph: &SymbolicAsyncGraph,
+    ) -> Result<GraphColoredVertices, String> {
+        if self.num_vars() != graph.as_network().num_vars() {
+            return Err(format!(
+                "Observation of {} variables used with a network of {} variables.",
+                self.num_vars(),
+                graph.as_network().num_vars()
+            ));
+        }
+        let context = graph.symbolic_context();
+        let valuation: Vec<_> = self
+            .values
+            .iter()
+            .zip(context.state_variables())
+            .filter_map(|(value, var)| value.map(|value| (*var, value)))
+            .collect();
+        let unit = graph.unit_colored_vertices();
+        Ok(unit.copy(unit.as_bdd().select(&valuation)))
+    }
+}
+
+/// Parse an observation from a string of `0` (false), `1` (true) and `*` or `-` (unknown)
+/// characters, one for each network variable.
+impl TryFrom<&str> for Observation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let values = value
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(Some(false)),
+                '1' => Ok(Some(true)),
+                '*' | '-' => Ok(None),
+                _ => Err(format!("Invalid observation value '{}' in '{}'.", c, value)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Observation { values })
+    }
+}
+
+impl Dataset {
+    /// Create a new dataset from the given `observations`.
+    ///
+    /// All observations must have the same number of variables.
+    pub fn new(observations: Vec<Observation>) -> Result<Dataset, String> {
+        if let Some(first) = observations.first() {
+            if let Some(other) = observations
+                .iter()
+                .find(|it| it.num_vars() != first.num_vars())
+            {
+                return Err(format!(
+                    "Observations of {} and {} variables cannot be mixed in one dataset.",
+                    first.num_vars(),
+                    other.num_vars()
+                ));
+            }
+        }
+        Ok(Dataset { observations })
+    }
+
+    /// The observations of this dataset, in their original order.
+    pub fn observations(&self) -> &[Observation] {
+        &self.observations
+    }
+}
+
+/// Compute the colors of the `graph` where every observation of the `dataset` matches
+/// at least one fixed point.
+///
+/// Returns an error if the observations do not match the variables of the network.
+pub fn consistent_with_fixed_points(
+    graph: &SymbolicAsyncGraph,
+    dataset: &Dataset,
+) -> Result<GraphColors, String> {
+    if dataset.observations().is_empty() {
+        return Ok(graph.mk_unit_colors());
+    }
+    let fixed_points = fixed_points(graph);
+    dataset
+        .observations()
+        .iter()
+        .try_fold(graph.mk_unit_colors(), |colors, observation| {
+            let states = observation.to_colored_vertices(graph)?;
+            Ok(colors.intersect(&fixed_points.intersect(&states).colors()))
+        })
+}
+
+/// Compute the colors of the `graph` where every observation of the `dataset` matches
+/// at least one attractor state (the observations can belong to different attractors).
+///
+/// Returns an error if the observations do not match the variables of the network.
+pub fn consistent_with_attractors(
+    graph: &SymbolicAsyncGraph,
+    dataset: &Dataset,
+) -> Result<GraphColors, String> {
+    dataset
+        .observations()
+        .iter()
+        .try_fold(graph.mk_unit_colors(), |colors, observation| {
+            let states = observation
+                .to_colored_vertices(graph)?
+                .intersect_colors(&colors);
+            // The forward reachable set is closed, hence its attractors are also attractors
+            // of the whole graph.
+            let universe = reach_fwd(graph, &states, graph.unit_colored_vertices());
+            let observed = attractors(graph, &universe)
+                .into_iter()
+                .fold(graph.mk_empty_colors(), |observed, attractor| {
+                    observed.union(&attractor.intersect(&states).colors())
+                });
+            Ok(colors.intersect(&observed))
+        })
+}
+
+/// Compute the colors of the `graph` which admit a path visiting the observations of
+/// the `dataset` in the given order, i.e. every observation is reachable from a matching
+/// state of the previous observation.
+///
+/// Returns an error if the observations do not match the variables of the network.
+pub fn consistent_with_time_series(
+    graph: &SymbolicAsyncGraph,
+    dataset: &Dataset,
+) -> Result<GraphColors, String> {
+    let unit = graph.unit_colored_vertices();
+    let mut observations = dataset.observations().iter().rev();
+    let last = match observations.next() {
+        Some(last) => last.to_colored_vertices(graph)?,
+        None => return Ok(graph.mk_unit_colors()),
+    };
+    // Vertices from which the rest of the time series can be observed.
+    let valid = observations.try_fold(last, |valid, observation| {
+        let states = observation.to_colored_vertices(graph)?;
+        Ok::<_, String>(states.intersect(&reach_bwd(graph, &valid, unit)))
+    })?;
+    Ok(valid.colors())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::inference::{
+        consistent_with_attractors, consistent_with_fixed_points, consistent_with_time_series,
+        Dataset, Observation,
+    };
+    use crate::symbolic_async_graph::{InstantiatedFunction, SymbolicAsyncGraph};
+    use crate::{BooleanNetwork, FnUpdate, ParameterId, VariableId};
+    use std::convert::TryFrom;
+
+    fn dataset(observations: &[&str]) -> Dataset {
+        let observations = observations
+            .iter()
+            .map(|it| Observation::try_from(*it).unwrap())
+            .collect();
+        Dataset::new(observations).unwrap()
+    }
+
+    fn network() -> SymbolicAsyncGraph {
+        // The update function of `a` is one of `false`, `true`, `b` and `!b`.
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -?? a
+            $a: f(b)
+            $b: a
+        ",
+        )
+        .unwrap();
+        SymbolicAsyncGraph::new(bn).unwrap()
+    }
+
+    #[test]
+    fn observations() {
+        let (a, b) = (VariableId(0), VariableId(1));
+        let observation = Observation::try_from("1-").unwrap();
+        assert_eq!(observation, Observation::from_values(2, &[(a, true)]));
+        assert_eq!(observation, Observation::new(vec![Some(true), None]));
+        assert_eq!(Some(true), observation.get_value(a));
+        assert_eq!(None, observation.get_value(b));
+        assert!(Observation::try_from("1x").is_err());
+        let short = Observation::try_from("1").unwrap();
+        assert!(Dataset::new(vec![observation.clone(), short.clone()]).is_err());
+
+        let stg = network();
+        let states = observation.to_colored_vertices(&stg).unwrap();
+        assert_eq!(states, stg.fix_network_variable(a, true));
+        assert!(short.to_colored_vertices(&stg).is_err());
+        let short = Dataset::new(vec![short]).unwrap();
+        assert!(consistent_with_fixed_points(&stg, &short).is_err());
+        assert!(consistent_with_attractors(&stg, &short).is_err());
+        assert!(consistent_with_time_series(&stg, &short).is_err());
+    }
+
+    #[test]
+    fn infer_from_fixed_points() {
+        let stg = network();
+        assert_eq!(4.0, stg.unit_colors().approx_cardinality());
+        let colors = consistent_with_fixed_points(&stg, &dataset(&["11"])).unwrap();
+        assert_eq!(2.0, colors.approx_cardinality());
+        assert_eq!(
+            colors,
+            consistent_with_fixed_points(&stg, &dataset(&["1*"])).unwrap()
+        );
+        assert!(consistent_with_fixed_points(&stg, &dataset(&["10"]))
+            .unwrap()
+            .is_empty());
+
+        // Only `f(b) = b` admits both fixed points.
+        let colors = consistent_with_fixed_points(&stg, &dataset(&["00", "11"])).unwrap();
+        let identity = FnUpdate::mk_var(VariableId(0));
+        let identity = InstantiatedFunction::from_fn_update(&identity, &[VariableId(0)]).unwrap();
+        assert_eq!(stg.fix_explicit_function(ParameterId(0), &identity), colors);
+
+        let empty = Dataset::new(Vec::new()).unwrap();
+        assert_eq!(
+            stg.unit_colors(),
+            &consistent_with_fixed_points(&stg, &empty).unwrap()
+        );
+    }
+
+    #[test]
+    fn infer_from_attractors() {
+        let stg = network();
+        // For `f(b) = !b`, the network oscillates through all states.
+        let colors = consistent_with_attractors(&stg, &dataset(&["10"])).unwrap();
+        assert_eq!(1.0, colors.approx_cardinality());
+        let colors = consistent_with_attractors(&stg, &dataset(&["11"])).unwrap();
+        assert_eq!(3.0, colors.approx_cardinality());
+        let fixed_point_colors = consistent_with_fixed_points(&stg, &dataset(&["11"])).unwrap();
+        assert!(fixed_point_colors.is_subset(&colors));
+        let colors = consistent_with_attractors(&stg, &dataset(&["00", "11"])).unwrap();
+        assert_eq!(2.0, colors.approx_cardinality());
+    }
+
+    #[test]
+    fn infer_from_time_series() {
+        let stg = network();
+        // `f(b) = true` and `f(b) = !b` can turn on `a`.
+        let colors = consistent_with_time_series(&stg, &dataset(&["00", "11"])).unwrap();
+        assert_eq!(2.0, colors.approx_cardinality());
+        // `f(b) = false` and `f(b) = !b` can turn off `a`.
+        let colors = consistent_with_time_series(&stg, &dataset(&["11", "00"])).unwrap();
+        assert_eq!(2.0, colors.approx_cardinality());
+        // Only `f(b) = !b` can do both.
+        let colors = consistent_with_time_series(&stg, &dataset(&["00", "11", "00"])).unwrap();
+        assert_eq!(1.0, colors.approx_cardinality());
+        assert_eq!(
+            colors,
+            consistent_with_attractors(&stg, &dataset(&["10"])).unwrap()
+        );
+    }
+}
@@ -17,8 +17,15 @@ use std::collections::HashMap;
 use std::iter::Map;
 use std::ops::Range;
 
+pub mod budget;
+pub mod control;
+pub mod ctl;
 pub mod decomposition;
+pub mod fixed_points;
+pub mod inference;
+pub mod reachability;
 pub mod stats;
+pub mod trap_spaces;
 
 pub mod async_graph;
 pub mod bdd_params;
@@ -51,10 +58,15 @@ mod _impl_parameter_id;
 mod _impl_regulation;
 /// **(internal)** Utility methods for `RegulatoryGraph`.
 mod _impl_regulatory_graph;
+/// **(internal)** Utility methods for `StaticConstraintError`.
+mod _impl_static_constraint_error;
 /// **(internal)** Utility methods for `Variable`.
 mod _impl_variable;
 /// **(internal)** Utility methods for `VariableId`.
 mod _impl_variable_id;
+/// **(internal)** Shared fixtures of unit tests.
+#[cfg(test)]
+mod _test_util;
 
 /// **(internal)** A regex string of an identifier which we currently allow to appear
 /// as a variable or parameter name.
@@ -230,6 +242,40 @@ pub struct BooleanNetwork {
     parameter_to_index: HashMap<String, ParameterId>,
 }
 
+/// A static constraint imposed on an update function by a `Regulation`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegulationConstraint {
+    /// The regulator must have an effect on the update function of the target.
+    Observability,
+    /// The regulator must influence the update function of the target with the given
+    /// monotonicity.
+    Monotonicity(Monotonicity),
+}
+
+/// A static constraint of a `Regulation` which cannot be satisfied by any admissible update
+/// function of its target (together with the constraints which were checked before it).
+///
+/// The `counterexample` is a valuation of the remaining regulators of the target: for
+/// monotonicity, the regulator has the opposite effect on the update function in this input,
+/// for observability, it has no effect in this input (and in any other input).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConstraintViolation {
+    regulation: Regulation,
+    constraint: RegulationConstraint,
+    counterexample: Vec<(VariableId, bool)>,
+}
+
+/// An error describing why no update functions of a `BooleanNetwork` satisfy the static
+/// constraints of its regulations.
+///
+/// The error keeps the `RegulatoryGraph` of the network, so that its `Display` implementation
+/// can provide a human-readable explanation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StaticConstraintError {
+    graph: RegulatoryGraph,
+    violations: Vec<ConstraintViolation>,
+}
+
 /// An iterator over all `VariableIds` of a `RegulatoryGraph` (or a `BooleanNetwork`).
 pub type VariableIdIterator = Map<Range<usize>, fn(usize) -> VariableId>;
 
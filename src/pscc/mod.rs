@@ -8,6 +8,7 @@
 */
 
 use crate::bdd_params::{build_static_constraints, BddParameterEncoder};
+use crate::budget::{Budget, Interrupted};
 use crate::{BooleanNetwork, VariableId};
 use biodivine_lib_bdd::{bdd, BddValuation};
 use biodivine_lib_bdd::{
@@ -37,7 +38,10 @@ pub struct PsccContext {
 }
 
 impl PsccContext {
-    pub fn new(bn: BooleanNetwork) -> PsccContext {
+    /// Create a new context for the given network.
+    ///
+    /// The error explains the violated static constraints of the network regulations.
+    pub fn new(bn: BooleanNetwork) -> Result<PsccContext, String> {
         let mut bdd = BddVariableSetBuilder::new();
         let mut explicit_function_tables: Vec<Vec<BddVariable>> = Vec::new();
         let mut implicit_function_tables: Vec<Vec<BddVariable>> = Vec::new();
@@ -101,7 +105,8 @@ impl PsccContext {
             implicit_function_tables: implicit_function_tables.clone(),
         };
 
-        let universe = build_static_constraints(&bn, &fake_encoder);
+        let universe = build_static_constraints(&bn, &fake_encoder)
+            .map_err(|error| error.to_string())?;
         println!("BDD vars: {}", bdd_variables.num_vars());
         println!("State space size: {}", (1.shl(state_variables.len()) as f64));
         println!("Param. universe cardinality: {}", universe.cardinality() / (1.shl(state_variables.len()) as f64));
@@ -165,14 +170,14 @@ impl PsccContext {
             println!("{}", u.to_dot_string(&bdd_variables, true));
         }*/
 
-        return PsccContext {
+        return Ok(PsccContext {
             p_count: bdd_variables.num_vars() - (bn.graph.num_vars() as u16),
             network: bn,
             bdd_variables,
             state_variables,
             update_function_cache: function_cache,
             universe,
-        };
+        });
     }
 
     fn extend_valuation_to_bdd(
@@ -618,45 +623,78 @@ impl ColorVertexSet {
     }
 }
 
-pub fn trim(context: &PsccContext, universe: ColorVertexSet) -> ColorVertexSet {
+/// Remove sinks and sources from the `universe` until no more can be removed.
+///
+/// The `budget` is checked after every step. On interruption, the partial result is
+/// the (partially trimmed) universe, i.e. a superset of the fully trimmed universe.
+pub fn trim(
+    context: &PsccContext,
+    universe: ColorVertexSet,
+    budget: &Budget,
+) -> Result<ColorVertexSet, Interrupted<ColorVertexSet>> {
     let start_cardinality = universe.cardinality();
     let mut result = universe;
 
     let to_trim = context.sinks(&result);
     result = result.minus(&to_trim);
+    check(budget, &result, &result)?;
     let mut test_next = context.pre(&to_trim, &result);
     while !test_next.is_empty() {
         let to_trim = context.sinks(&test_next);
         if LOG_LEVEL > 0 { println!("To trim: {}", to_trim.cardinality()); }
         result = result.minus(&to_trim);
+        check(budget, &result, &result)?;
         if TRIM_CUTOFF && to_trim.cardinality() < 0.001 * start_cardinality { break; }
         test_next = context.pre(&to_trim, &result);
     }
 
     let to_trim = context.sources(&result);
     result = result.minus(&to_trim);
+    check(budget, &result, &result)?;
     let mut test_next = context.post(&to_trim, &result);
     while !test_next.is_empty() {
         let to_trim = context.sources(&result);
         if LOG_LEVEL > 0 { println!("To trim: {}", to_trim.cardinality()); }
         result = result.minus(&to_trim);
+        check(budget, &result, &result)?;
         if TRIM_CUTOFF && to_trim.cardinality() < 0.001 * start_cardinality { break; }
         test_next = context.post(&to_trim, &result);
     }
 
     let trimmed = start_cardinality - result.bdd.cardinality();
     println!("Trimmed: {} ({}%)", trimmed, ((trimmed / start_cardinality) * 100.0) as usize);
-    return result;
+    return Ok(result);
+}
+
+/// **(internal)** Check the `budget`, assuming `latest` is the last set computed by a fixpoint.
+/// On interruption, `partial` becomes the partial result of the error.
+fn check(
+    budget: &Budget,
+    latest: &ColorVertexSet,
+    partial: &ColorVertexSet,
+) -> Result<(), Interrupted<ColorVertexSet>> {
+    return budget
+        .check(&latest.bdd)
+        .map_err(|reason| Interrupted::new(reason, partial.clone()));
 }
 
-pub fn decomposition(context: &PsccContext, universe: ColorVertexSet, iterations: &AtomicU32) {
+/// Decompose the `universe` into SCCs, exploring the independent parts in parallel.
+///
+/// The `budget` is checked after every fixpoint step. On interruption, the partial result
+/// is the part of the `universe` that has not been decomposed yet.
+pub fn decomposition(
+    context: &PsccContext,
+    universe: ColorVertexSet,
+    iterations: &AtomicU32,
+    budget: &Budget,
+) -> Result<(), Interrupted<ColorVertexSet>> {
     if universe.is_empty() {
-        return;
+        return Ok(());
     }
     println!("Decomposition: {}", universe.cardinality());
-    let universe = trim(context, universe);
+    let universe = trim(context, universe, budget)?;
     if universe.is_empty() {
-        return;
+        return Ok(());
     }
     let pivot = context.pivots(&universe);
     println!("Found pivot: {:?}", pivot.cardinality());
@@ -677,6 +715,8 @@ pub fn decomposition(context: &PsccContext, universe: ColorVertexSet, iterations
         let ref new_b_frontier = context.pre(&b_frontier, &universe.minus(&b));
         f = f.union(new_f_frontier);
         b = b.union(new_b_frontier);
+        check(budget, &f, &universe)?;
+        check(budget, &b, &universe)?;
         let stopped_f_colors = context
             .color_projection(&f_frontier)
             .minus(&context.color_projection(&new_f_frontier));
@@ -704,11 +744,13 @@ pub fn decomposition(context: &PsccContext, universe: ColorVertexSet, iterations
     while !cont_f_frontier.intersect(&b).is_empty() {
         cont_f_frontier = context.post(&cont_f_frontier, &b.minus(&f));
         f = f.union(&cont_f_frontier);
+        check(budget, &f, &universe)?;
     }
 
     while !cont_b_frontier.intersect(&f).is_empty() {
         cont_b_frontier = context.pre(&cont_b_frontier, &f.minus(&b));
         b = b.union(&cont_b_frontier);
+        check(budget, &b, &universe)?;
     }
 
     let scc = f.intersect(&b);
@@ -725,8 +767,17 @@ pub fn decomposition(context: &PsccContext, universe: ColorVertexSet, iterations
 
     iterations.fetch_add(1, Ordering::SeqCst);
 
-    rayon::join(
-        || decomposition(context, universe.minus(&converged), iterations),
-        || decomposition(context, converged.minus(&scc), iterations),
+    let (left, right) = rayon::join(
+        || decomposition(context, universe.minus(&converged), iterations, budget),
+        || decomposition(context, converged.minus(&scc), iterations, budget),
     );
+    // If both parts were interrupted, the remaining universe is the union of both partial results.
+    return match (left, right) {
+        (Err(left), Err(right)) => Err(Interrupted::new(
+            left.reason,
+            left.partial.union(&right.partial),
+        )),
+        (Err(error), Ok(())) | (Ok(()), Err(error)) => Err(error),
+        (Ok(()), Ok(())) => Ok(()),
+    };
 }
@@ -0,0 +1,294 @@
+//! Symbolic reachability on the `SymbolicAsyncGraph` using saturation.
+//!
+//! Instead of applying all update functions at once (as `post` and `pre` do), saturation
+//! always applies the first update function (in some fixed `SaturationOrder`) which extends
+//! the current set, and then starts again from the beginning of the order. In practice, this
+//! keeps the intermediate BDDs much smaller than the "breadth-first" exploration.
+//!
+//! The default variants are also available as `SymbolicAsyncGraph::reach_fwd` and
+//! `SymbolicAsyncGraph::reach_bwd`.
+
+use crate::biodivine_std::traits::Set;
+use crate::budget::{Budget, Interrupted};
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+use crate::{BooleanNetwork, VariableId};
+
+/// Heuristics for the order in which saturation applies the update functions of the network.
+/// Variables at the beginning of the order have the highest priority.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum SaturationOrder {
+    /// Variables in the order in which they appear in the network.
+    Network,
+    /// Variables in the reversed network order. This is the default order, also used by
+    /// the decomposition algorithms.
+    #[default]
+    Reversed,
+    /// Variables with fewer regulators (i.e. simpler update functions) go first. Ties
+    /// are resolved using the reversed network order.
+    FewestRegulators,
+    /// An explicit order of variables. Variables which are not listed are updated last
+    /// (in the reversed network order), so that the reachable set is always complete.
+    Custom(Vec<VariableId>),
+}
+
+impl SaturationOrder {
+    /// List the variables of the `network` in this order.
+    pub fn variables(&self, network: &BooleanNetwork) -> Vec<VariableId> {
+        match self {
+            SaturationOrder::Network => network.variables().collect(),
+            SaturationOrder::Reversed => network.variables().rev().collect(),
+            SaturationOrder::FewestRegulators => {
+                let mut variables: Vec<VariableId> = network.variables().rev().collect();
+                variables.sort_by_key(|it| network.regulators(*it).len());
+                variables
+            }
+            SaturationOrder::Custom(variables) => {
+                let mut result = variables.clone();
+                for variable in network.variables().rev() {
+                    if !result.contains(&variable) {
+                        result.push(variable);
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Compute all vertices of the `universe` which are forward-reachable from the `initial`
+/// set (including the `initial` set itself) without leaving the `universe`.
+pub fn reach_fwd(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    reach_fwd_ordered(graph, initial, universe, &SaturationOrder::default())
+}
+
+/// Compute all vertices of the `universe` which are backward-reachable from the `initial`
+/// set (including the `initial` set itself) without leaving the `universe`.
+pub fn reach_bwd(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    reach_bwd_ordered(graph, initial, universe, &SaturationOrder::default())
+}
+
+/// Same as `reach_fwd_ordered`, but the computation stops once the given `budget` is exhausted.
+///
+/// The partial result of the error contains the vertices reached so far, i.e. it is a subset
+/// of the forward-reachable vertices which contains the `initial` set.
+pub fn reach_fwd_with_budget(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    order: &SaturationOrder,
+    budget: &Budget,
+) -> Result<GraphColoredVertices, Interrupted<GraphColoredVertices>> {
+    let variables = order.variables(graph.as_network());
+    saturation(graph, initial.clone(), universe, &variables, true, |set| {
+        budget
+            .check(set.as_bdd())
+            .map_err(|reason| Interrupted::new(reason, set.clone()))
+    })
+}
+
+/// Same as `reach_bwd_ordered`, but the computation stops once the given `budget` is exhausted.
+///
+/// The partial result of the error contains the vertices reached so far, i.e. it is a subset
+/// of the backward-reachable vertices which contains the `initial` set.
+pub fn reach_bwd_with_budget(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    order: &SaturationOrder,
+    budget: &Budget,
+) -> Result<GraphColoredVertices, Interrupted<GraphColoredVertices>> {
+    let variables = order.variables(graph.as_network());
+    saturation(graph, initial.clone(), universe, &variables, false, |set| {
+        budget
+            .check(set.as_bdd())
+            .map_err(|reason| Interrupted::new(reason, set.clone()))
+    })
+}
+
+/// Same as `reach_fwd`, but uses the given saturation `order`.
+pub fn reach_fwd_ordered(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    order: &SaturationOrder,
+) -> GraphColoredVertices {
+    let variables = order.variables(graph.as_network());
+    saturation(graph, initial.clone(), universe, &variables, true, |_| {
+        Ok(())
+    })
+    .expect("Saturation without a budget cannot be interrupted.")
+}
+
+/// Same as `reach_bwd`, but uses the given saturation `order`.
+pub fn reach_bwd_ordered(
+    graph: &SymbolicAsyncGraph,
+    initial: &GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    order: &SaturationOrder,
+) -> GraphColoredVertices {
+    let variables = order.variables(graph.as_network());
+    saturation(graph, initial.clone(), universe, &variables, false, |_| {
+        Ok(())
+    })
+    .expect("Saturation without a budget cannot be interrupted.")
+}
+
+/// **(internal)** The saturation procedure shared by all reachability algorithms.
+///
+/// The `on_step` callback is called with the current set before each saturation step
+/// and can interrupt the computation (e.g. when a budget is exhausted).
+pub(crate) fn saturation<F>(
+    graph: &SymbolicAsyncGraph,
+    mut set: GraphColoredVertices,
+    universe: &GraphColoredVertices,
+    variables: &[VariableId],
+    forward: bool,
+    mut on_step: F,
+) -> Result<GraphColoredVertices, Interrupted<GraphColoredVertices>>
+where
+    F: FnMut(&GraphColoredVertices) -> Result<(), Interrupted<GraphColoredVertices>>,
+{
+    'saturation: loop {
+        on_step(&set)?;
+        for var in variables {
+            let step = if forward {
+                graph.var_post(*var, &set)
+            } else {
+                graph.var_pre(*var, &set)
+            };
+            let step = step.intersect(universe);
+            if !step.is_subset(&set) {
+                set = set.union(&step);
+                continue 'saturation;
+            }
+        }
+        return Ok(set);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::budget::{Budget, InterruptReason};
+    use crate::reachability::{
+        reach_bwd, reach_bwd_ordered, reach_bwd_with_budget, reach_fwd, reach_fwd_ordered,
+        reach_fwd_with_budget, SaturationOrder,
+    };
+    use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    /// A cascade where the update of `a` closes a feedback loop in some colors.
+    fn cascade_network() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -> c
+            c -> d
+            d -?? a
+            $b: a
+            $c: b
+            $d: c
+        ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn saturation_agrees_with_post_and_pre() {
+        let stg = SymbolicAsyncGraph::new(cascade_network()).unwrap();
+        let closure = |initial: &GraphColoredVertices, universe: &GraphColoredVertices, fwd| {
+            let mut result = initial.clone();
+            loop {
+                let step = if fwd {
+                    stg.post(&result)
+                } else {
+                    stg.pre(&result)
+                };
+                let step = step.intersect(universe);
+                if step.is_subset(&result) {
+                    return result;
+                }
+                result = result.union(&step);
+            }
+        };
+
+        let unit = stg.unit_colored_vertices();
+        let a = stg.fix_network_variable(
+            stg.as_network().as_graph().find_variable("a").unwrap(),
+            true,
+        );
+        let initial = unit.pick_vertex();
+        let orders = vec![
+            SaturationOrder::Network,
+            SaturationOrder::Reversed,
+            SaturationOrder::FewestRegulators,
+            SaturationOrder::Custom(stg.as_network().variables().collect()),
+        ];
+        for universe in &[unit.clone(), a.clone()] {
+            let initial = initial.intersect(universe);
+            let fwd = closure(&initial, universe, true);
+            let bwd = closure(&initial, universe, false);
+            assert_eq!(fwd, reach_fwd(&stg, &initial, universe));
+            assert_eq!(bwd, reach_bwd(&stg, &initial, universe));
+            for order in &orders {
+                assert_eq!(fwd, reach_fwd_ordered(&stg, &initial, universe, order));
+                assert_eq!(bwd, reach_bwd_ordered(&stg, &initial, universe, order));
+            }
+        }
+
+        // Variables missing in a custom order are still updated.
+        let d = stg.as_network().as_graph().find_variable("d").unwrap();
+        let partial = SaturationOrder::Custom(vec![d]);
+        let empty = SaturationOrder::Custom(Vec::new());
+        for order in &[partial, empty] {
+            let fwd = reach_fwd(&stg, &initial, unit);
+            let bwd = reach_bwd(&stg, &initial, unit);
+            assert_eq!(fwd, reach_fwd_ordered(&stg, &initial, unit, order));
+            assert_eq!(bwd, reach_bwd_ordered(&stg, &initial, unit, order));
+        }
+        assert_eq!(
+            reach_fwd(&stg, &initial, unit),
+            stg.reach_fwd(&initial, unit)
+        );
+        assert_eq!(
+            reach_bwd(&stg, &initial, unit),
+            stg.reach_bwd(&initial, unit)
+        );
+    }
+
+    #[test]
+    fn interrupted_reachability() {
+        let stg = SymbolicAsyncGraph::new(cascade_network()).unwrap();
+        let unit = stg.unit_colored_vertices();
+        let initial = unit.pick_vertex();
+        let fwd = reach_fwd(&stg, &initial, unit);
+        let bwd = reach_bwd(&stg, &initial, unit);
+        let unlimited = Budget::unlimited();
+        for order in &[SaturationOrder::Network, SaturationOrder::FewestRegulators] {
+            let result = reach_fwd_with_budget(&stg, &initial, unit, order, &unlimited);
+            assert_eq!(fwd, result.unwrap());
+            let result = reach_bwd_with_budget(&stg, &initial, unit, order, &unlimited);
+            assert_eq!(bwd, result.unwrap());
+        }
+
+        let order = SaturationOrder::default();
+        let budget = Budget::unlimited().with_max_bdd_size(1);
+        let error = reach_fwd_with_budget(&stg, &initial, unit, &order, &budget).unwrap_err();
+        assert_eq!(InterruptReason::BddSizeExceeded, error.reason);
+        assert!(initial.is_subset(&error.partial));
+        assert!(error.partial.is_subset(&fwd));
+        let error = reach_bwd_with_budget(&stg, &initial, unit, &order, &budget).unwrap_err();
+        assert_eq!(InterruptReason::BddSizeExceeded, error.reason);
+        assert!(initial.is_subset(&error.partial));
+        assert!(error.partial.is_subset(&bwd));
+    }
+}
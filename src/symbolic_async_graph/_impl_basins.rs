@@ -0,0 +1,239 @@
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use biodivine_lib_bdd::{Bdd, BddVariable};
+use num_bigint::BigInt;
+use std::collections::{BTreeMap, HashMap};
+
+/// Basins of attraction.
+///
+/// All methods work with colored sets, i.e. the basins are computed for all colors of
+/// the attractor at once.
+impl SymbolicAsyncGraph {
+    /// Compute the weak basin of the given colored `attractor`, i.e. all vertices which can
+    /// reach the attractor (including the attractor itself).
+    pub fn weak_basin(&self, attractor: &GraphColoredVertices) -> GraphColoredVertices {
+        self.reach_bwd(attractor, self.unit_colored_vertices())
+    }
+
+    /// Compute the strong basin of the given colored `attractor`, i.e. all vertices which
+    /// can reach the attractor but cannot reach any other attractor. Every path starting in
+    /// the strong basin thus eventually enters the attractor.
+    ///
+    /// When the set is not an attractor, the result contains the vertices from which every
+    /// reachable attractor intersects the set.
+    pub fn strong_basin(&self, attractor: &GraphColoredVertices) -> GraphColoredVertices {
+        let unit = self.unit_colored_vertices();
+        let weak_basin = self.weak_basin(attractor);
+        let can_escape = self.reach_bwd(&unit.minus(&weak_basin), unit);
+        weak_basin.minus(&can_escape)
+    }
+
+    /// Compute the relative size of the given `basin` (i.e. the fraction of the state space
+    /// it covers) for every color of the basin. Colors with the same relative size are
+    /// grouped together and the groups are sorted by increasing size.
+    ///
+    /// The colors are not enumerated. Instead, the basin is split into cofactors of all state
+    /// variables and for every cofactor, the colors are partitioned by the exact number of
+    /// basin vertices. The cost thus depends on the number of distinct sizes, not on
+    /// the number of colors.
+    pub fn relative_basin_size(&self, basin: &GraphColoredVertices) -> Vec<(GraphColors, f64)> {
+        let context = &self.symbolic_context;
+        let mut variables = context.state_variables.clone();
+        variables.extend(context.parameter_variables.iter().cloned());
+        variables.sort();
+        let other_variables: Vec<BddVariable> = context
+            .bdd
+            .variables()
+            .into_iter()
+            .filter(|var| variables.binary_search(var).is_err())
+            .collect();
+        let mut counter = ColorPartitionCounter {
+            state_variables: context.state_variables.clone(),
+            unit: context.mk_constant(true),
+            cache: HashMap::new(),
+        };
+        let state_count = context.state_variables.len() as i32;
+        counter
+            .count(&basin.as_bdd().project(&other_variables), 0)
+            .into_iter()
+            .filter(|(count, _)| *count > BigInt::from(0))
+            .map(|(count, colors)| {
+                (
+                    self.unit_colors().copy(colors),
+                    relative_size(&count, state_count),
+                )
+            })
+            .collect()
+    }
+}
+
+/// **(internal)** Partitions the colors of a colored set by the number of vertices
+/// that appear in the set for each color.
+///
+/// It is a variant of the `ValuationCounter` where instead of a single number, every cofactor
+/// maps to a partition of all colors, indexed by the number of valuations of the remaining
+/// state variables.
+struct ColorPartitionCounter {
+    state_variables: Vec<BddVariable>,
+    unit: Bdd,
+    cache: HashMap<(usize, Bdd), BTreeMap<BigInt, Bdd>>,
+}
+
+impl ColorPartitionCounter {
+    /// Partition the colors of the `bdd` (which only depends on the state variables starting
+    /// at the given `level` and the parameter variables) by the number of vertices.
+    fn count(&mut self, bdd: &Bdd, level: usize) -> BTreeMap<BigInt, Bdd> {
+        let mut result = BTreeMap::new();
+        if bdd.is_false() || bdd.is_true() {
+            let count = if bdd.is_true() {
+                BigInt::from(1) << (self.state_variables.len() - level)
+            } else {
+                BigInt::from(0)
+            };
+            result.insert(count, self.unit.clone());
+            return result;
+        }
+        if level == self.state_variables.len() {
+            result.insert(BigInt::from(1), bdd.clone());
+            result.insert(BigInt::from(0), bdd.not());
+            return result;
+        }
+        let key = (level, bdd.clone());
+        if let Some(partition) = self.cache.get(&key) {
+            return partition.clone();
+        }
+        let var = self.state_variables[level];
+        let low = bdd.var_select(var, false).var_project(var);
+        let high = bdd.var_select(var, true).var_project(var);
+        let low = self.count(&low, level + 1);
+        let high = self.count(&high, level + 1);
+        for (low_count, low_colors) in &low {
+            for (high_count, high_colors) in &high {
+                let colors = low_colors.and(high_colors);
+                if colors.is_false() {
+                    continue;
+                }
+                let count = low_count + high_count;
+                let colors = match result.remove(&count) {
+                    Some(other) => other.or(&colors),
+                    None => colors,
+                };
+                result.insert(count, colors);
+            }
+        }
+        self.cache.insert(key, result.clone());
+        result
+    }
+}
+
+/// **(internal)** Compute `count / 2^bits` as a floating point number without overflowing
+/// for large `bits`.
+fn relative_size(count: &BigInt, bits: i32) -> f64 {
+    // Keep only the 64 most significant bits of the `count`.
+    let shift = count.bits().saturating_sub(64);
+    let mantissa = (count >> shift)
+        .to_u64_digits()
+        .1
+        .first()
+        .cloned()
+        .unwrap_or(0);
+    (mantissa as f64) * 2.0f64.powi(shift as i32 - bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::bitvector::ArrayBitVector;
+    use crate::biodivine_std::traits::Set;
+    use crate::fixed_points::fixed_points;
+    use crate::symbolic_async_graph::_impl_basins::relative_size;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, VariableId};
+    use num_bigint::BigInt;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn basins_of_toggle_switch() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            a -> c
+            $a: !b
+            $b: !a
+            $c: a
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let attractor = stg.vertex(&ArrayBitVector::from(vec![true, false, true]));
+        let weak = stg.weak_basin(&attractor);
+        let strong = stg.strong_basin(&attractor);
+        assert!(attractor.is_subset(&strong));
+        assert!(strong.is_subset(&weak));
+        // Only the states `01*` cannot reach the attractor.
+        assert_eq!(6.0, weak.approx_cardinality());
+        // Only the states `10*` cannot reach the other attractor.
+        assert_eq!(2.0, strong.approx_cardinality());
+        assert_eq!(
+            vec![(stg.mk_unit_colors(), 0.75)],
+            stg.relative_basin_size(&weak)
+        );
+        assert_eq!(
+            vec![(stg.mk_unit_colors(), 0.25)],
+            stg.relative_basin_size(&strong)
+        );
+        assert!(stg.relative_basin_size(stg.empty_vertices()).is_empty());
+    }
+
+    #[test]
+    fn basin_sizes_per_color() {
+        let bn = BooleanNetwork::try_from("a -?? a \n $a: f(a)").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        // The fixed point `a = 1` exists for `f(a) = a` and `f(a) = true`.
+        let a_true = stg.vertex(&ArrayBitVector::from(vec![true]));
+        let attractor = fixed_points(&stg).intersect(&a_true);
+        assert_eq!(2.0, attractor.colors().approx_cardinality());
+
+        let weak = stg.weak_basin(&attractor);
+        let strong = stg.strong_basin(&attractor);
+        let weak_sizes = stg.relative_basin_size(&weak);
+        let strong_sizes = stg.relative_basin_size(&strong);
+        assert_eq!(2, weak_sizes.len());
+        assert_eq!(weak_sizes, strong_sizes);
+        for (colors, size) in weak_sizes {
+            assert_eq!(1.0, colors.approx_cardinality());
+            // With `f(a) = true`, every state reaches the fixed point.
+            let basin = weak.intersect_colors(&colors);
+            if basin.approx_cardinality() == 2.0 {
+                assert_eq!(1.0, size);
+            } else {
+                assert_eq!(0.5, size);
+            }
+        }
+    }
+
+    #[test]
+    fn basin_sizes_agree_with_enumeration() {
+        let bn = BooleanNetwork::try_from("a -?? b \n b -?? a \n a -?? a \n b -?? c").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let a_true = stg.fix_network_variable(VariableId(0), true);
+        let weak = stg.weak_basin(&fixed_points(&stg).intersect(&a_true));
+        let sizes = stg.relative_basin_size(&weak);
+        assert_eq!(3, sizes.len());
+        let mut covered = stg.mk_empty_colors();
+        for (colors, size) in &sizes {
+            assert!(covered.intersect(colors).is_empty());
+            covered = covered.union(colors);
+            let mut remaining = colors.clone();
+            while !remaining.is_empty() {
+                let color = remaining.pick_singleton();
+                remaining = remaining.minus(&color);
+                let basin = weak.intersect_colors(&color).vertices();
+                assert_eq!(*size, basin.approx_cardinality() / 8.0);
+            }
+        }
+        assert_eq!(weak.colors(), covered);
+        assert_eq!(1.0, relative_size(&(BigInt::from(1) << 2000), 2000));
+        assert_eq!(0.75, relative_size(&(BigInt::from(3) << 2000), 2002));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::{
+    ColorModel, GraphColors, InstantiatedFunction, SymbolicAsyncGraph, WitnessIterator,
+};
+use crate::{BooleanNetwork, FnUpdate, ParameterId, VariableId};
+use rand::Rng;
+
+impl SymbolicAsyncGraph {
+    /// Lazily iterate over the witness networks of all colors in the given set.
+    ///
+    /// Note that the number of colors can be very large, so consider using `Iterator::take`
+    /// or `sample_witnesses`. Colors which only differ in perturbations have the same
+    /// witness, hence every such witness is only returned once.
+    pub fn witnesses(&self, colors: &GraphColors) -> WitnessIterator<'_> {
+        WitnessIterator {
+            graph: self,
+            remaining: self.project_perturbations(colors),
+        }
+    }
+
+    /// Pick at most `count` distinct random colors from the given set and return their
+    /// witness networks.
+    ///
+    /// The colors are sampled uniformly (see `GraphColors::pick_random`) without repetition.
+    /// As in `witnesses`, colors which only differ in perturbations are considered the same.
+    pub fn sample_witnesses<R: Rng>(
+        &self,
+        colors: &GraphColors,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<BooleanNetwork> {
+        let mut remaining = self.project_perturbations(colors);
+        let mut result = Vec::new();
+        while result.len() < count && !remaining.is_empty() {
+            let color = self.project_perturbations(&remaining.pick_random(rng));
+            result.push(self.pick_witness(&color));
+            remaining = remaining.minus(&color);
+        }
+        result
+    }
+
+    /// Instantiate all uninterpreted functions of one color in the given set.
+    ///
+    /// The perturbation variables of the color (if any) do not influence the result.
+    ///
+    /// Panic: The set must not be empty.
+    pub fn instantiate_color(&self, colors: &GraphColors) -> ColorModel {
+        if colors.is_empty() {
+            panic!("Cannot instantiate an empty color set.");
+        }
+        let valuation = colors.bdd.sat_witness().unwrap();
+        let context = &self.symbolic_context;
+        let explicit = self
+            .network
+            .parameters()
+            .map(|parameter| {
+                let table = context.get_explicit_function_table(parameter);
+                InstantiatedFunction::from_table(table, &valuation)
+            })
+            .collect();
+        let implicit = self
+            .network
+            .variables()
+            .map(|variable| {
+                context.implicit_function_tables[variable.0]
+                    .as_ref()
+                    .map(|table| {
+                        let args = self.network.regulators(variable);
+                        InstantiatedFunction::from_table(table, &valuation).to_fn_update(&args)
+                    })
+            })
+            .collect();
+        ColorModel { explicit, implicit }
+    }
+}
+
+impl Iterator for WitnessIterator<'_> {
+    type Item = BooleanNetwork;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let color = self
+            .graph
+            .project_perturbations(&self.remaining.pick_singleton());
+        self.remaining = self.remaining.minus(&color);
+        Some(self.graph.pick_witness(&color))
+    }
+}
+
+impl ColorModel {
+    /// The instantiated function of the given explicit `parameter`.
+    pub fn get_explicit_function(&self, parameter: ParameterId) -> &InstantiatedFunction {
+        &self.explicit[parameter.0]
+    }
+
+    /// The instantiated implicit update function of the given `variable`, or `None` if
+    /// the variable has an explicit update function.
+    pub fn get_implicit_function(&self, variable: VariableId) -> Option<&FnUpdate> {
+        self.implicit[variable.0].as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, FnUpdate, ParameterId, VariableId};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn enumerate_witnesses() {
+        let stg = SymbolicAsyncGraph::new(tutorial_network()).unwrap();
+        let colors = stg.unit_colors();
+        let count = colors.approx_cardinality() as usize;
+
+        let witnesses: Vec<BooleanNetwork> = stg.witnesses(colors).collect();
+        assert_eq!(count, witnesses.len());
+        let distinct: HashSet<String> = witnesses.iter().map(|it| it.to_string()).collect();
+        assert_eq!(count, distinct.len());
+        for witness in witnesses {
+            let witness_stg = SymbolicAsyncGraph::new(witness).unwrap();
+            assert_eq!(1.0, witness_stg.unit_colors().approx_cardinality());
+        }
+        assert!(stg.witnesses(stg.empty_colors()).next().is_none());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let sample = stg.sample_witnesses(colors, 3, &mut rng);
+        let sample: HashSet<String> = sample.iter().map(|it| it.to_string()).collect();
+        assert_eq!(3, sample.len());
+        assert!(sample.is_subset(&distinct));
+        assert_eq!(count, stg.sample_witnesses(colors, 100, &mut rng).len());
+    }
+
+    #[test]
+    fn instantiate_color() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            a -?? a
+            $a: f(a, b)
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        let ctx = stg.symbolic_context();
+        let (a, b) = (VariableId(0), VariableId(1));
+
+        let mut remaining = stg.mk_unit_colors();
+        let mut functions = HashSet::new();
+        while !remaining.is_empty() {
+            let color = remaining.pick_singleton();
+            remaining = remaining.minus(&color);
+            let model = stg.instantiate_color(&color);
+            let witness = stg.pick_witness(&color);
+
+            // The update function of `a` is `f(a, b)`, hence the formal arguments of `f`
+            // correspond to `a` and `b`.
+            let f = model.get_explicit_function(ParameterId(0));
+            assert_eq!(2, f.arity());
+            let a_function = witness.get_update_function(a).as_ref().unwrap();
+            let f_of_a_b = f.to_fn_update(&[a, b]);
+            assert_eq!(
+                ctx.mk_fn_update_true(a_function),
+                ctx.mk_fn_update_true(&f_of_a_b)
+            );
+            assert_eq!(Some(&FnUpdate::mk_var(a)), model.get_implicit_function(b));
+            assert!(model.get_implicit_function(a).is_none());
+            functions.insert(f.clone());
+        }
+        assert_eq!(
+            stg.unit_colors().approx_cardinality() as usize,
+            functions.len()
+        );
+    }
+}
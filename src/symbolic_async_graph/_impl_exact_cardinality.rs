@@ -0,0 +1,149 @@
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, GraphVertices};
+use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+/// Exact cardinality.
+///
+/// Unlike `approx_cardinality`, the result is an arbitrary-precision integer, so it does not
+/// lose precision for large sets. However, it is also considerably slower: every cofactor of
+/// the underlying `Bdd` is computed using two BDD operations, and is then cloned and hashed
+/// to be cached. The cost is therefore roughly quadratic in the size of the `Bdd` (compared
+/// to linear for `approx_cardinality`).
+impl GraphColors {
+    /// Exact number of colors in this set.
+    pub fn exact_cardinality(&self) -> BigInt {
+        exact_cardinality_of(&self.bdd, &self.parameter_variables)
+    }
+}
+
+/// Exact cardinality (see `GraphColors`).
+impl GraphVertices {
+    /// Exact number of vertices in this set.
+    pub fn exact_cardinality(&self) -> BigInt {
+        exact_cardinality_of(&self.bdd, &self.state_variables)
+    }
+}
+
+/// Exact cardinality (see `GraphColors`).
+impl GraphColoredVertices {
+    /// Exact number of (vertex, color) pairs in this set.
+    pub fn exact_cardinality(&self) -> BigInt {
+        let mut variables = self.state_variables.clone();
+        variables.extend(self.parameter_variables.iter().cloned());
+        exact_cardinality_of(&self.bdd, &variables)
+    }
+}
+
+/// **(internal)** Exact number of valuations (of all variables) which satisfy the `bdd`.
+pub(crate) fn exact_bdd_cardinality(bdd: &Bdd) -> BigInt {
+    let variables = BddVariableSet::new_anonymous(bdd.num_vars()).variables();
+    ValuationCounter::new(variables).count(bdd, 0)
+}
+
+/// **(internal)** Exact number of valuations of the given `variables` which appear in
+/// the `bdd`. The remaining variables are ignored (projected).
+fn exact_cardinality_of(bdd: &Bdd, variables: &[BddVariable]) -> BigInt {
+    let mut variables = variables.to_vec();
+    variables.sort();
+    let other_variables: Vec<BddVariable> = BddVariableSet::new_anonymous(bdd.num_vars())
+        .variables()
+        .into_iter()
+        .filter(|var| variables.binary_search(var).is_err())
+        .collect();
+    ValuationCounter::new(variables).count(&bdd.project(&other_variables), 0)
+}
+
+/// **(internal)** Counts the satisfying valuations of a fixed list of (sorted) `variables`
+/// by recursively splitting a `Bdd` into its cofactors. The counts of all visited cofactors
+/// are cached, so every cofactor is only counted once.
+///
+/// Note that the cache is keyed by whole `Bdd` objects, so each step clones and hashes
+/// the current cofactor. This makes counting roughly quadratic in the size of the `Bdd`.
+pub(crate) struct ValuationCounter {
+    variables: Vec<BddVariable>,
+    cache: HashMap<(usize, Bdd), BigInt>,
+}
+
+impl ValuationCounter {
+    /// Create a counter of valuations of the given `variables` (which must be sorted).
+    pub(crate) fn new(variables: Vec<BddVariable>) -> ValuationCounter {
+        ValuationCounter {
+            variables,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Split the `bdd` into the cofactors for `false` and `true` values of the variable
+    /// at the given `level`. The cofactors do not depend on this variable.
+    pub(crate) fn cofactors(&self, bdd: &Bdd, level: usize) -> (Bdd, Bdd) {
+        let var = self.variables[level];
+        let low = bdd.var_select(var, false).var_project(var);
+        let high = bdd.var_select(var, true).var_project(var);
+        (low, high)
+    }
+
+    /// Number of valuations of the variables starting at the given `level` which satisfy
+    /// the `bdd`. The `bdd` must not depend on any other variables.
+    pub(crate) fn count(&mut self, bdd: &Bdd, level: usize) -> BigInt {
+        if bdd.is_false() {
+            return BigInt::from(0);
+        }
+        if bdd.is_true() {
+            return BigInt::from(1) << (self.variables.len() - level);
+        }
+        let key = (level, bdd.clone());
+        if let Some(count) = self.cache.get(&key) {
+            return count.clone();
+        }
+        let (low, high) = self.cofactors(bdd, level);
+        let count = if low == high {
+            self.count(&low, level + 1) << 1
+        } else {
+            self.count(&low, level + 1) + self.count(&high, level + 1)
+        };
+        self.cache.insert(key, count.clone());
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use num_bigint::BigInt;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn exact_cardinality() {
+        // Monotonicity leaves three admissible functions for each variable.
+        let bn = BooleanNetwork::try_from("a ->? b \n b -|? a \n c -> c").unwrap();
+        let stg = SymbolicAsyncGraph::with_extra_state_variables(bn, 1).unwrap();
+        let a = stg.as_network().as_graph().find_variable("a").unwrap();
+        let unit = stg.unit_colored_vertices();
+        let sets = vec![
+            unit.clone(),
+            stg.fix_network_variable(a, true),
+            unit.pick_vertex(),
+            stg.mk_empty_vertices(),
+        ];
+        for set in sets {
+            let approx = |value: f64| BigInt::from(value as u64);
+            assert_eq!(approx(set.approx_cardinality()), set.exact_cardinality());
+            let colors = set.colors();
+            assert_eq!(
+                approx(colors.approx_cardinality()),
+                colors.exact_cardinality()
+            );
+            let vertices = set.vertices();
+            assert_eq!(
+                approx(vertices.approx_cardinality()),
+                vertices.exact_cardinality()
+            );
+        }
+        assert_eq!(BigInt::from(8), unit.vertices().exact_cardinality());
+        assert_eq!(BigInt::from(9), unit.colors().exact_cardinality());
+        assert!(!unit.is_empty());
+    }
+}
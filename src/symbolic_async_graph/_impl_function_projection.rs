@@ -0,0 +1,286 @@
+use crate::symbolic_async_graph::{
+    FunctionTable, GraphColors, InstantiatedFunction, SymbolicAsyncGraph,
+};
+use crate::{BinaryOp, FnUpdate, ParameterId, VariableId};
+use biodivine_lib_bdd::{Bdd, BddValuation, BddValuationIterator, BddVariable};
+use std::convert::TryInto;
+
+/// Projection of color sets onto individual uninterpreted functions.
+impl SymbolicAsyncGraph {
+    /// Compute all distinct instantiations of the explicit `parameter` which appear
+    /// in the given color set.
+    pub fn explicit_function_projection(
+        &self,
+        colors: &GraphColors,
+        parameter: ParameterId,
+    ) -> Vec<InstantiatedFunction> {
+        let table = self.symbolic_context.get_explicit_function_table(parameter);
+        function_projection(colors.as_bdd(), table)
+    }
+
+    /// Compute all distinct instantiations of the implicit update function of the given
+    /// `variable` which appear in the given color set.
+    ///
+    /// Panic: Variable must have an implicit uninterpreted function.
+    pub fn implicit_function_projection(
+        &self,
+        colors: &GraphColors,
+        variable: VariableId,
+    ) -> Vec<InstantiatedFunction> {
+        let table = self.symbolic_context.get_implicit_function_table(variable);
+        function_projection(colors.as_bdd(), table)
+    }
+
+    /// Create a color set (a subset of `unit_colors`) where the explicit `parameter` is
+    /// instantiated to the given `function`.
+    ///
+    /// Panic: The arity of the `function` must match the arity of the `parameter`.
+    pub fn fix_explicit_function(
+        &self,
+        parameter: ParameterId,
+        function: &InstantiatedFunction,
+    ) -> GraphColors {
+        let table = self.symbolic_context.get_explicit_function_table(parameter);
+        self.fix_function_table(table, function)
+    }
+
+    /// Create a color set (a subset of `unit_colors`) where the implicit update function of
+    /// the given `variable` is instantiated to the given `function`.
+    ///
+    /// Panic: Variable must have an implicit uninterpreted function and the arity of
+    /// the `function` must match the number of its regulators.
+    pub fn fix_implicit_function(
+        &self,
+        variable: VariableId,
+        function: &InstantiatedFunction,
+    ) -> GraphColors {
+        let table = self.symbolic_context.get_implicit_function_table(variable);
+        self.fix_function_table(table, function)
+    }
+
+    /// **(internal)** Restrict the unit color set to the given instantiation of the `table`.
+    fn fix_function_table(
+        &self,
+        table: &FunctionTable,
+        function: &InstantiatedFunction,
+    ) -> GraphColors {
+        if usize::from(table.arity) != function.arity() {
+            panic!(
+                "Function of arity {} cannot instantiate a function table of arity {}.",
+                function.arity(),
+                table.arity
+            );
+        }
+        let valuation: Vec<(BddVariable, bool)> = table
+            .into_iter()
+            .map(|(inputs, var)| (var, function.evaluate(&inputs)))
+            .collect();
+        self.unit_colors().copy(self.unit_bdd.select(&valuation))
+    }
+}
+
+impl InstantiatedFunction {
+    /// Create a function of the given `args` (i.e. the arity of the function is
+    /// `args.len()`) by evaluating the `function` for every valuation of the `args`.
+    ///
+    /// The `function` can only depend on the `args` and cannot contain parameters.
+    pub fn from_fn_update(
+        function: &FnUpdate,
+        args: &[VariableId],
+    ) -> Result<InstantiatedFunction, String> {
+        let arity: u16 = args
+            .len()
+            .try_into()
+            .map_err(|_| "Too many function arguments.".to_string())?;
+        // Use the same row order as the `FunctionTable`.
+        let rows = BddValuationIterator::new(arity)
+            .map(|row| {
+                let inputs = row.vector();
+                evaluate(function, args, &inputs).map(|value| (inputs, value))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(InstantiatedFunction { rows })
+    }
+
+    /// **(internal)** Read the instantiation of the `table` from the given `valuation`.
+    pub(super) fn from_table(table: &FunctionTable, valuation: &BddValuation) -> Self {
+        InstantiatedFunction {
+            rows: table
+                .into_iter()
+                .map(|(inputs, var)| (inputs, valuation[var]))
+                .collect(),
+        }
+    }
+
+    /// Number of arguments of this function.
+    pub fn arity(&self) -> usize {
+        self.rows
+            .first()
+            .map(|(inputs, _)| inputs.len())
+            .unwrap_or(0)
+    }
+
+    /// The value of this function for the given input valuation of its arguments.
+    ///
+    /// Panic: The number of `inputs` must be the arity of this function.
+    pub fn evaluate(&self, inputs: &[bool]) -> bool {
+        let row = self.rows.iter().find(|(row, _)| row.as_slice() == inputs);
+        let (_, value) = row.unwrap_or_else(|| panic!("Invalid function inputs {:?}.", inputs));
+        *value
+    }
+
+    /// Convert this function into an `FnUpdate`, where the i-th argument of the function
+    /// is the network variable `args[i]`.
+    pub fn to_fn_update(&self, args: &[VariableId]) -> FnUpdate {
+        let rows: Vec<(&[bool], bool)> = self
+            .rows
+            .iter()
+            .map(|(inputs, value)| (inputs.as_slice(), *value))
+            .collect();
+        shannon_expansion(&rows, args)
+    }
+}
+
+/// **(internal)** Collect all distinct instantiations of the `table` in the `colors` bdd.
+fn function_projection(colors: &Bdd, table: &FunctionTable) -> Vec<InstantiatedFunction> {
+    let rows: Vec<BddVariable> = table.into_iter().map(|(_, var)| var).collect();
+    let mut remaining = colors.clone();
+    let mut result = Vec::new();
+    while let Some(witness) = remaining.sat_witness() {
+        let function = InstantiatedFunction::from_table(table, &witness);
+        let valuation: Vec<(BddVariable, bool)> =
+            rows.iter().map(|var| (*var, witness[*var])).collect();
+        // Remove all colors with the same instantiation of the table.
+        remaining = remaining.and_not(&remaining.select(&valuation));
+        result.push(function);
+    }
+    result
+}
+
+/// **(internal)** Evaluate the `function` when the `args` have the given `values`.
+fn evaluate(function: &FnUpdate, args: &[VariableId], values: &[bool]) -> Result<bool, String> {
+    Ok(match function {
+        FnUpdate::Const(value) => *value,
+        FnUpdate::Var(id) => match args.iter().position(|arg| arg == id) {
+            Some(index) => values[index],
+            None => return Err(format!("Variable {:?} is not an argument.", id)),
+        },
+        FnUpdate::Not(inner) => !evaluate(inner, args, values)?,
+        FnUpdate::Param(id, _) => return Err(format!("Unexpected parameter {:?}.", id)),
+        FnUpdate::Binary(op, left, right) => {
+            let l = evaluate(left, args, values)?;
+            let r = evaluate(right, args, values)?;
+            match op {
+                BinaryOp::And => l && r,
+                BinaryOp::Or => l || r,
+                BinaryOp::Xor => l ^ r,
+                BinaryOp::Imp => !l || r,
+                BinaryOp::Iff => l == r,
+            }
+        }
+    })
+}
+
+/// **(internal)** Build an `FnUpdate` for a function given by its table `rows` using
+/// the Shannon expansion on the first argument.
+fn shannon_expansion(rows: &[(&[bool], bool)], args: &[VariableId]) -> FnUpdate {
+    if rows.iter().all(|(_, value)| *value) {
+        return FnUpdate::mk_true();
+    }
+    if rows.iter().all(|(_, value)| !*value) {
+        return FnUpdate::mk_false();
+    }
+    let cofactor = |arg_value: bool| {
+        let rows: Vec<(&[bool], bool)> = rows
+            .iter()
+            .filter(|(row, _)| row[0] == arg_value)
+            .map(|(row, value)| (&row[1..], *value))
+            .collect();
+        shannon_expansion(&rows, &args[1..])
+    };
+    let (high, low) = (cofactor(true), cofactor(false));
+    let var = FnUpdate::mk_var(args[0]);
+    if high == low {
+        return high;
+    }
+    match (high.as_const(), low.as_const()) {
+        (Some(true), Some(false)) => var,
+        (Some(false), Some(true)) => var.negation(),
+        (Some(true), None) => var.or(low),
+        (Some(false), None) => var.negation().and(low),
+        (None, Some(true)) => var.negation().or(high),
+        (None, Some(false)) => var.and(high),
+        _ => var.clone().and(high).or(var.negation().and(low)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::{InstantiatedFunction, SymbolicAsyncGraph};
+    use crate::{BooleanNetwork, FnUpdate, ParameterId, VariableId};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn function_projection() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            c -|? b
+            b -| a
+            a -?? a
+            c -> c
+            $a: f(a, b)
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        let (a, b) = (VariableId(0), VariableId(1));
+        let f = ParameterId(0);
+        let unit = stg.unit_colors();
+
+        // Every color instantiates `f` to one of the projected functions.
+        let f_functions = stg.explicit_function_projection(unit, f);
+        let mut f_colors = stg.mk_empty_colors();
+        for function in &f_functions {
+            assert_eq!(2, function.arity());
+            let fixed = stg.fix_explicit_function(f, function);
+            assert!(!fixed.is_empty());
+            assert_eq!(
+                vec![function.clone()],
+                stg.explicit_function_projection(&fixed, f)
+            );
+            f_colors = f_colors.union(&fixed);
+
+            // Converting to `FnUpdate` and back gives the same function.
+            let formal = [VariableId(0), VariableId(1)];
+            let fn_update = function.to_fn_update(&formal);
+            assert_eq!(
+                function,
+                &InstantiatedFunction::from_fn_update(&fn_update, &formal).unwrap()
+            );
+        }
+        assert_eq!(unit, &f_colors);
+
+        // The implicit function of `b` is `a & !c`, `a | !c` or `a` (`c` is not observable).
+        let b_functions = stg.implicit_function_projection(unit, b);
+        let regulators = bn.regulators(b);
+        let b_functions: Vec<FnUpdate> = b_functions
+            .iter()
+            .map(|it| it.to_fn_update(&regulators))
+            .collect();
+        assert_eq!(3, b_functions.len());
+        assert!(b_functions.contains(&FnUpdate::mk_var(a)));
+
+        let a_only = InstantiatedFunction::from_fn_update(&FnUpdate::mk_var(a), &regulators);
+        let a_only = stg.fix_implicit_function(b, &a_only.unwrap());
+        let witness = stg.pick_witness(&a_only);
+        assert_eq!(&Some(FnUpdate::mk_var(a)), witness.get_update_function(b));
+
+        // Functions which do not fit the arguments are rejected.
+        let not_c = FnUpdate::mk_var(VariableId(2)).negation();
+        assert!(InstantiatedFunction::from_fn_update(&not_c, &[a, b]).is_err());
+        let param = FnUpdate::mk_param(f, &[a, b]);
+        assert!(InstantiatedFunction::from_fn_update(&param, &[a, b]).is_err());
+    }
+}
@@ -45,8 +45,15 @@ impl GraphColoredVertices {
     }
 
     /// Approximate size of this set (error grows for large sets).
+    ///
+    /// For a precise result, use `exact_cardinality`, which is however roughly quadratic
+    /// (instead of linear) in the size of the underlying `Bdd`.
     pub fn approx_cardinality(&self) -> f64 {
-        self.bdd.cardinality()
+        // Extra variables of the symbolic context are not constrained by the set.
+        let symbolic_variable_count = self.state_variables.len() + self.parameter_variables.len();
+        let extra_variable_count = usize::from(self.bdd.num_vars()) - symbolic_variable_count;
+        let extra_count = 2.0f64.powi(extra_variable_count as i32);
+        self.bdd.cardinality() / extra_count
     }
 }
 
@@ -49,6 +49,9 @@ impl GraphColors {
     }
 
     /// Approximate size of this set (error grows for large sets).
+    ///
+    /// For a precise result, use `exact_cardinality`, which is however roughly quadratic
+    /// (instead of linear) in the size of the underlying `Bdd`.
     pub fn approx_cardinality(&self) -> f64 {
         let state_variable_count =
             self.bdd.num_vars() - u16::try_from(self.parameter_variables.len()).unwrap();
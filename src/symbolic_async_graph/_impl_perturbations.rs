@@ -0,0 +1,272 @@
+use crate::symbolic_async_graph::{
+    ExtraVariablePosition, GraphColoredVertices, GraphColors, SymbolicAsyncGraph, SymbolicContext,
+};
+use crate::{BooleanNetwork, VariableId};
+use biodivine_lib_bdd::{Bdd, BddVariable};
+
+/// Perturbations of the network.
+///
+/// A perturbation fixes the value of some variables, e.g. a knockout (the variable is always
+/// false) or an over-expression (the variable is always true). A graph can either represent
+/// one concrete perturbation (`with_fixed_variables`), or all perturbations of the chosen
+/// variables at once (`with_perturbations`).
+impl SymbolicAsyncGraph {
+    /// Create a graph of the network where the given variables are fixed to constants
+    /// (see `BooleanNetwork::fix_variables`).
+    pub fn with_fixed_variables(
+        network: BooleanNetwork,
+        values: &[(VariableId, bool)],
+    ) -> Result<SymbolicAsyncGraph, String> {
+        SymbolicAsyncGraph::new(network.fix_variables(values))
+    }
+
+    /// Create a graph where every variable from `perturbable` can be perturbed.
+    ///
+    /// Each such variable has an extra BDD variable `{name}_perturbed`. When it is true,
+    /// the update function of the variable is disabled, i.e. the variable keeps its initial
+    /// value (the variable is knocked-out in states where it is false and over-expressed in
+    /// states where it is true). The perturbation variables are considered to be part of
+    /// the colors of the graph, hence every color represents one parametrisation together with
+    /// one perturbation. Witness networks do not depend on perturbations, so the witness
+    /// methods (e.g. `witnesses`) project the perturbation variables away first
+    /// (see `project_perturbations`).
+    pub fn with_perturbations(
+        network: BooleanNetwork,
+        perturbable: &[VariableId],
+    ) -> Result<SymbolicAsyncGraph, String> {
+        let names: Vec<String> = perturbable
+            .iter()
+            .map(|var| {
+                if var.0 < network.num_vars() {
+                    Ok(format!("{}_perturbed", network.get_variable_name(*var)))
+                } else {
+                    Err(format!("Unknown network variable {:?}.", var))
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let extra: Vec<(&str, ExtraVariablePosition)> = names
+            .iter()
+            .zip(perturbable)
+            .map(|(name, var)| {
+                let position = ExtraVariablePosition::AfterStateVariable(*var);
+                (name.as_str(), position)
+            })
+            .collect();
+        let mut context = SymbolicContext::with_extra_variables(&network, 0, &extra)?;
+        let mut perturbations = vec![None; network.num_vars()];
+        for (var, name) in perturbable.iter().zip(&names) {
+            let bdd_var = context.find_extra_variable(name).unwrap();
+            perturbations[var.0] = Some(bdd_var);
+            context.parameter_variables.push(bdd_var);
+        }
+        context.parameter_variables.sort();
+        SymbolicAsyncGraph::build(network, context, perturbations)
+    }
+
+    /// The BDD variable which is true when the given `variable` is perturbed, or `None`
+    /// if the variable cannot be perturbed in this graph.
+    pub fn perturbation_variable(&self, variable: VariableId) -> Option<BddVariable> {
+        self.perturbations[variable.0]
+    }
+
+    /// Remove the perturbation variables from the given color set, i.e. the result contains
+    /// every parametrisation of `colors` combined with all possible perturbations.
+    ///
+    /// In a graph without perturbations, the set is returned unchanged.
+    pub fn project_perturbations(&self, colors: &GraphColors) -> GraphColors {
+        let variables: Vec<BddVariable> = self.perturbations.iter().flatten().cloned().collect();
+        colors.copy(colors.bdd.project(&variables))
+    }
+
+    /// Create a color set where exactly the given variables are perturbed.
+    ///
+    /// Panic: All variables must be perturbable.
+    pub fn mk_perturbed_colors(&self, perturbed: &[VariableId]) -> GraphColors {
+        for var in perturbed {
+            if self.perturbation_variable(*var).is_none() {
+                panic!("Variable {:?} cannot be perturbed.", var);
+            }
+        }
+        let mut valuation = Vec::new();
+        for (i, bdd_var) in self.perturbations.iter().enumerate() {
+            if let Some(bdd_var) = bdd_var {
+                valuation.push((*bdd_var, perturbed.contains(&VariableId(i))));
+            }
+        }
+        self.unit_colors().copy(self.unit_bdd.select(&valuation))
+    }
+
+    /// Create a color set where at most `size` variables are perturbed.
+    pub fn mk_perturbations_up_to(&self, size: usize) -> GraphColors {
+        // `exactly[k]` contains valuations of the processed variables with `k` perturbations.
+        let mut exactly: Vec<Bdd> = vec![self.symbolic_context.mk_constant(true)];
+        for bdd_var in self.perturbations.iter().flatten() {
+            let is_perturbed = self.symbolic_context.bdd.mk_var(*bdd_var);
+            let mut next = Vec::with_capacity(exactly.len() + 1);
+            for k in 0..=exactly.len().min(size) {
+                let not_perturbed = exactly.get(k).map(|it| it.and_not(&is_perturbed));
+                let perturbed = k
+                    .checked_sub(1)
+                    .and_then(|k| exactly.get(k))
+                    .map(|it| it.and(&is_perturbed));
+                let both = match (not_perturbed, perturbed) {
+                    (Some(a), Some(b)) => a.or(&b),
+                    (Some(a), None) | (None, Some(a)) => a,
+                    (None, None) => unreachable!(),
+                };
+                next.push(both);
+            }
+            exactly = next;
+        }
+        let at_most = exactly
+            .into_iter()
+            .fold(self.symbolic_context.mk_constant(false), |a, b| a.or(&b));
+        self.unit_colors().copy(self.unit_bdd.and(&at_most))
+    }
+
+    /// Create a colored vertex set where exactly the given variables are perturbed and
+    /// fixed to the given values (e.g. `(var, false)` is a knockout of `var`).
+    ///
+    /// Panic: All variables must be perturbable.
+    pub fn fix_perturbation(&self, values: &[(VariableId, bool)]) -> GraphColoredVertices {
+        let perturbed: Vec<VariableId> = values.iter().map(|(var, _)| *var).collect();
+        let colors = self.mk_perturbed_colors(&perturbed);
+        let state: Vec<(BddVariable, bool)> = values
+            .iter()
+            .map(|(var, value)| (self.symbolic_context.state_variables[var.0], *value))
+            .collect();
+        GraphColoredVertices::new(colors.bdd.select(&state), &self.symbolic_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+    use crate::{BooleanNetwork, VariableId};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn fix_network_variables() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            c -> a
+            a -? c
+            $a: !b & f(c)
+            $b: a
+            $c: g(a)
+        ",
+        )
+        .unwrap();
+        let (a, b) = (VariableId(0), VariableId(1));
+        let fixed = bn.fix_variables(&[(a, true)]);
+        assert_eq!(1, fixed.num_parameters());
+        assert!(fixed.regulators(a).is_empty());
+        assert_eq!(bn.regulators(b), fixed.regulators(b));
+        assert_eq!("g", fixed[fixed.parameters().next().unwrap()].get_name());
+
+        let stg = SymbolicAsyncGraph::with_fixed_variables(bn, &[(a, true)]).unwrap();
+        // Once `a` is true, it cannot change.
+        let a_true = stg.fix_network_variable(a, true);
+        assert!(stg.var_post(a, &a_true).is_empty());
+        assert_eq!(
+            a_true,
+            stg.var_post(a, stg.unit_colored_vertices()).union(&a_true)
+        );
+    }
+
+    #[test]
+    fn perturbations_agree_with_fixed_variables() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            c -> a
+            a -| c
+            c -> c
+            $a: !b & c
+            $b: a
+            $c: !a | c
+        ",
+        )
+        .unwrap();
+        let variables: Vec<VariableId> = bn.variables().collect();
+        let stg = SymbolicAsyncGraph::with_perturbations(bn.clone(), &variables).unwrap();
+        assert_eq!(8.0, stg.unit_colors().approx_cardinality());
+        assert_eq!(1.0, stg.mk_perturbations_up_to(0).approx_cardinality());
+        assert_eq!(4.0, stg.mk_perturbations_up_to(1).approx_cardinality());
+        assert_eq!(7.0, stg.mk_perturbations_up_to(2).approx_cardinality());
+        assert_eq!(8.0, stg.mk_perturbations_up_to(5).approx_cardinality());
+        assert_eq!(stg.mk_perturbed_colors(&[]), stg.mk_perturbations_up_to(0));
+
+        let fixed_points = |graph: &SymbolicAsyncGraph, set: &GraphColoredVertices| {
+            set.minus(&graph.can_post(set))
+                .vertices()
+                .approx_cardinality()
+        };
+        let mut mutants: Vec<Vec<(VariableId, bool)>> = vec![Vec::new()];
+        for var in &variables {
+            for value in &[false, true] {
+                mutants.push(vec![(*var, *value)]);
+                for other in variables.iter().filter(|it| *it > var) {
+                    mutants.push(vec![(*var, *value), (*other, !*value)]);
+                }
+            }
+        }
+        for mutant in mutants {
+            let fixed = SymbolicAsyncGraph::with_fixed_variables(bn.clone(), &mutant).unwrap();
+            let universe = stg.fix_perturbation(&mutant);
+            assert_eq!(
+                fixed_points(&fixed, fixed.unit_colored_vertices()),
+                fixed_points(&stg, &universe)
+            );
+            let reachable = stg.post(&universe).minus(&universe);
+            assert!(reachable.is_empty());
+        }
+    }
+
+    #[test]
+    fn perturbations_do_not_duplicate_witnesses() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -?? b
+            b -| a
+            $a: !b
+        ",
+        )
+        .unwrap();
+        let variables: Vec<VariableId> = bn.variables().collect();
+        let plain = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        let stg = SymbolicAsyncGraph::with_perturbations(bn, &variables).unwrap();
+        assert_eq!(4.0, plain.unit_colors().approx_cardinality());
+        assert_eq!(16.0, stg.unit_colors().approx_cardinality());
+
+        let projected = stg.project_perturbations(&stg.mk_perturbed_colors(&[variables[0]]));
+        assert_eq!(stg.unit_colors(), &projected);
+        assert_eq!(
+            plain.unit_colors(),
+            &plain.project_perturbations(plain.unit_colors())
+        );
+
+        let witnesses: HashSet<String> = stg
+            .witnesses(stg.unit_colors())
+            .map(|it| it.to_string())
+            .collect();
+        let expected: HashSet<String> = plain
+            .witnesses(plain.unit_colors())
+            .map(|it| it.to_string())
+            .collect();
+        assert_eq!(4, stg.witnesses(stg.unit_colors()).count());
+        assert_eq!(expected, witnesses);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            4,
+            stg.sample_witnesses(stg.unit_colors(), 10, &mut rng).len()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::symbolic_async_graph::_impl_exact_cardinality::ValuationCounter;
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, GraphVertices};
+use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
+use num_bigint::{BigInt, Sign};
+use rand::Rng;
+
+/// Uniform random sampling.
+///
+/// The element is chosen uniformly among all elements of the set (unlike `pick_singleton`,
+/// which always picks the first one), using the provided random number generator.
+impl GraphColors {
+    /// Pick one color from this set uniformly at random and return it as a singleton.
+    ///
+    /// If the set is empty, return empty set.
+    pub fn pick_random<R: Rng>(&self, rng: &mut R) -> GraphColors {
+        self.copy(pick_random(&self.bdd, &self.parameter_variables, rng))
+    }
+}
+
+/// Uniform random sampling (see `GraphColors`).
+impl GraphVertices {
+    /// Pick one vertex from this set uniformly at random and return it as a singleton.
+    ///
+    /// If the set is empty, return empty set.
+    pub fn pick_random<R: Rng>(&self, rng: &mut R) -> GraphVertices {
+        self.copy(pick_random(&self.bdd, &self.state_variables, rng))
+    }
+}
+
+/// Uniform random sampling (see `GraphColors`).
+impl GraphColoredVertices {
+    /// Pick one (vertex, color) pair from this set uniformly at random and return it
+    /// as a singleton.
+    ///
+    /// If the set is empty, return empty set.
+    pub fn pick_random<R: Rng>(&self, rng: &mut R) -> GraphColoredVertices {
+        let mut variables = self.state_variables.clone();
+        variables.extend(self.parameter_variables.iter().cloned());
+        self.copy(pick_random(&self.bdd, &variables, rng))
+    }
+}
+
+/// **(internal)** Pick a uniformly random valuation of the given `variables` which appears
+/// in the `bdd` and fix it in the `bdd`. Other variables of the `bdd` are ignored (projected)
+/// when counting the valuations.
+fn pick_random<R: Rng>(bdd: &Bdd, variables: &[BddVariable], rng: &mut R) -> Bdd {
+    if bdd.is_false() {
+        return bdd.clone();
+    }
+    let mut variables = variables.to_vec();
+    variables.sort();
+    let other_variables: Vec<BddVariable> = BddVariableSet::new_anonymous(bdd.num_vars())
+        .variables()
+        .into_iter()
+        .filter(|var| variables.binary_search(var).is_err())
+        .collect();
+
+    // Walk through the cofactors of the projected `Bdd`, choosing the value of every variable
+    // proportionally to the exact number of valuations in the respective cofactor.
+    let mut counter = ValuationCounter::new(variables.clone());
+    let mut remaining = bdd.project(&other_variables);
+    let mut valuation = Vec::with_capacity(variables.len());
+    for (level, var) in variables.iter().enumerate() {
+        let (low, high) = counter.cofactors(&remaining, level);
+        let low_count = counter.count(&low, level + 1);
+        let high_count = counter.count(&high, level + 1);
+        let value = random_below(&(&low_count + &high_count), rng) >= low_count;
+        remaining = if value { high } else { low };
+        valuation.push((*var, value));
+    }
+    bdd.select(&valuation)
+}
+
+/// **(internal)** Pick a uniformly random integer from `[0, bound)` (`bound` must be positive).
+fn random_below<R: Rng>(bound: &BigInt, rng: &mut R) -> BigInt {
+    let (_, mut bytes) = bound.to_bytes_le();
+    // Clear the bits above the highest bit of `bound`, so that at least half of
+    // the candidates are accepted.
+    let unused_bits = 8 * bytes.len() as u64 - bound.bits();
+    let mask = u8::MAX >> unused_bits;
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let last = bytes.len() - 1;
+        bytes[last] &= mask;
+        let candidate = BigInt::from_bytes_le(Sign::Plus, &bytes);
+        if &candidate < bound {
+            return candidate;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn uniform_random_sampling() {
+        // Monotonicity leaves three admissible functions for each variable, so the colors
+        // do not form a power of two.
+        let bn = BooleanNetwork::try_from("a ->? b \n b -|? a").unwrap();
+        let stg = SymbolicAsyncGraph::with_extra_state_variables(bn, 1).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let a = stg.as_network().as_graph().find_variable("a").unwrap();
+        // An unbalanced set: all colors for `A = 1`, but only one color for `A = 0`.
+        let set = stg.fix_network_variable(a, true).union(
+            &stg.fix_network_variable(a, false)
+                .intersect_colors(&stg.unit_colors().pick_singleton()),
+        );
+        let set_size = set.approx_cardinality() as usize;
+
+        let mut counts = HashMap::new();
+        let samples = 200 * set_size;
+        for _ in 0..samples {
+            let singleton = set.pick_random(&mut rng);
+            assert_eq!(1.0, singleton.approx_cardinality());
+            assert!(singleton.is_subset(&set));
+            *counts.entry(singleton.into_bdd()).or_insert(0) += 1;
+        }
+        // Every element is picked, and roughly equally often.
+        assert_eq!(set_size, counts.len());
+        for count in counts.values() {
+            assert!(*count > 100 && *count < 300, "{}", count);
+        }
+
+        let colors = set.colors();
+        let vertices = set.vertices();
+        for _ in 0..10 {
+            let color = colors.pick_random(&mut rng);
+            assert_eq!(1.0, color.approx_cardinality());
+            assert!(color.is_subset(&colors));
+            let vertex = vertices.pick_random(&mut rng);
+            assert_eq!(1.0, vertex.approx_cardinality());
+            assert!(vertex.is_subset(&vertices));
+        }
+        assert!(stg.mk_empty_vertices().pick_random(&mut rng).is_empty());
+    }
+}
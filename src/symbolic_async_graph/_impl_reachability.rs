@@ -0,0 +1,26 @@
+use crate::reachability;
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+
+/// Saturation-based reachability using the default `SaturationOrder`. See the `reachability`
+/// module for custom orders and budgets.
+impl SymbolicAsyncGraph {
+    /// Compute all vertices of the `universe` which are forward-reachable from the `initial`
+    /// set (including the `initial` set itself) without leaving the `universe`.
+    pub fn reach_fwd(
+        &self,
+        initial: &GraphColoredVertices,
+        universe: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        reachability::reach_fwd(self, initial, universe)
+    }
+
+    /// Compute all vertices of the `universe` which are backward-reachable from the `initial`
+    /// set (including the `initial` set itself) without leaving the `universe`.
+    pub fn reach_bwd(
+        &self,
+        initial: &GraphColoredVertices,
+        universe: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        reachability::reach_bwd(self, initial, universe)
+    }
+}
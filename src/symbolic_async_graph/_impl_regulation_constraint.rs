@@ -1,19 +1,37 @@
 use crate::symbolic_async_graph::SymbolicContext;
-use crate::{BooleanNetwork, Monotonicity};
+use crate::{
+    BooleanNetwork, ConstraintViolation, Monotonicity, Regulation, RegulationConstraint,
+    StaticConstraintError, VariableId,
+};
 use biodivine_lib_bdd::{bdd, Bdd};
 
+impl SymbolicContext {
+    /// Compute the parameter valuations which satisfy the static constraints (observability
+    /// and monotonicity) of all regulations in the given `network`.
+    ///
+    /// If there are no such valuations, the error lists the violated constraints
+    /// (see `apply_regulation_constraints`).
+    pub fn mk_regulation_constraints(
+        &self,
+        network: &BooleanNetwork,
+    ) -> Result<Bdd, StaticConstraintError> {
+        apply_regulation_constraints(self.mk_constant(true), network, self)
+    }
+}
+
 /// Compute a `Bdd` which is a subset of the `initial` valuations that satisfies all
 /// constraints imposed by the given Boolean `network`.
 ///
-/// If there are no satisfying valuations, this function should return a human-readable
-/// message which explains problem (often in several lines). However, in some complex
-/// cases (inter-dependent parameters), this can be very hard and the error messages
-/// are thus purely best effort service.
+/// The constraints are applied one by one, in the order of the network regulations. If
+/// a constraint cannot be satisfied together with the constraints applied before it, it is
+/// reported as violated (including a counterexample input) and skipped. Consequently, in some
+/// complex cases (inter-dependent parameters), the reported violations depend on the order
+/// of the regulations, but every unsatisfiable network reports at least one violation.
 pub(crate) fn apply_regulation_constraints(
     initial: Bdd,
     network: &BooleanNetwork,
     context: &SymbolicContext,
-) -> Result<Bdd, String> {
+) -> Result<Bdd, StaticConstraintError> {
     // For each variable, compute Bdd that is true exactly when its update function is true.
     let update_function_is_true: Vec<Bdd> = network
         .variables()
@@ -26,7 +44,7 @@ pub(crate) fn apply_regulation_constraints(
         })
         .collect();
 
-    let mut error_message = String::new();
+    let mut violations = Vec::new();
     let mut unit_bdd = initial;
     for regulation in &network.graph.regulations {
         let regulator = context.state_variables[regulation.regulator.0];
@@ -44,26 +62,32 @@ pub(crate) fn apply_regulation_constraints(
                    b <- \exists s_r: F(s_1, ..., s_r, ..., s_n, p_1, ..., p_m) = 1 and s_r = 0
                    a != b
         */
-        let observability = if regulation.observable {
+        if regulation.observable {
             // \exists x_r : F(x_1, ..., x_r, ..., x_n) & x_r | Context where F is one for x_r, but with x_r erased.
             let fn_x1_to_1 = bdd!(fn_is_true & regulator_is_true).var_project(regulator);
             // \exists x_r : F(x_1, ..., x_r, ..., x_m) & !x_r | Context where F is one for !x_r, but with x_r erased.
             let fn_x0_to_1 = bdd!(fn_is_true & regulator_is_false).var_project(regulator);
             // Context where F for x_r is not equal F for !x_r (i.e. all witnesses of observability)
             // and then with all states erased.
-            bdd!(fn_x1_to_1 ^ fn_x0_to_1).project(&context.state_variables)
-        } else {
-            context.mk_constant(true)
-        };
+            let observable_inputs = bdd!(fn_x1_to_1 ^ fn_x0_to_1);
+            let observability = observable_inputs.project(&context.state_variables);
 
-        /* If observability failed, report error and continue. */
-        if observability.is_false() {
-            let problem = format!(
-                " - {} has no effect in {}.\n",
-                network.get_variable_name(regulation.regulator),
-                network.get_variable_name(regulation.target),
-            );
-            error_message = format!("{}{}", error_message, problem);
+            /* If observability failed, report error and continue. */
+            let restricted = bdd!(unit_bdd & observability);
+            if restricted.is_false() {
+                violations.push(ConstraintViolation {
+                    regulation: regulation.clone(),
+                    constraint: RegulationConstraint::Observability,
+                    counterexample: counterexample(
+                        network,
+                        context,
+                        regulation,
+                        &bdd!(unit_bdd & (!observable_inputs)),
+                    ),
+                });
+            } else {
+                unit_bdd = restricted;
+            }
         }
 
         /*
@@ -74,45 +98,111 @@ pub(crate) fn apply_regulation_constraints(
                    b <- \exists s_r: F(s_1, ..., s_r, ..., s_n, p_1, ..., p_m) = 1 and s_r = 0
                    a & b   // "I can go from 1 to 0 by increasing s_r."
         */
-        let non_monotonous = match regulation.monotonicity {
-            Some(Monotonicity::Activation) => {
-                let fn_x1_to_0 = bdd!(fn_is_false & regulator_is_true).var_project(regulator);
-                let fn_x0_to_1 = bdd!(fn_is_true & regulator_is_false).var_project(regulator);
-                bdd!(fn_x0_to_1 & fn_x1_to_0).project(&context.state_variables)
-            }
-            Some(Monotonicity::Inhibition) => {
-                let fn_x0_to_0 = bdd!(fn_is_false & regulator_is_false).var_project(regulator);
-                let fn_x1_to_1 = bdd!(fn_is_true & regulator_is_true).var_project(regulator);
-                bdd!(fn_x0_to_0 & fn_x1_to_1).project(&context.state_variables)
-            }
-            None => context.mk_constant(false),
-        };
-        let monotonicity = non_monotonous.not();
-
-        if monotonicity.is_false() {
-            let monotonicity_str = match regulation.monotonicity {
-                Some(Monotonicity::Activation) => "activating",
-                Some(Monotonicity::Inhibition) => "inhibiting",
-                None => "monotonous",
+        if let Some(monotonicity) = regulation.monotonicity {
+            let non_monotonous_inputs = match monotonicity {
+                Monotonicity::Activation => {
+                    let fn_x1_to_0 = bdd!(fn_is_false & regulator_is_true).var_project(regulator);
+                    let fn_x0_to_1 = bdd!(fn_is_true & regulator_is_false).var_project(regulator);
+                    bdd!(fn_x0_to_1 & fn_x1_to_0)
+                }
+                Monotonicity::Inhibition => {
+                    let fn_x0_to_0 = bdd!(fn_is_false & regulator_is_false).var_project(regulator);
+                    let fn_x1_to_1 = bdd!(fn_is_true & regulator_is_true).var_project(regulator);
+                    bdd!(fn_x0_to_0 & fn_x1_to_1)
+                }
             };
-            let problem = format!(
-                " - {} not {} in {}.\n",
-                network.get_variable_name(regulation.regulator),
-                monotonicity_str,
-                network.get_variable_name(regulation.target),
-            );
-            error_message = format!("{}{}", error_message, problem);
-        }
+            let non_monotonous = non_monotonous_inputs.project(&context.state_variables);
 
-        unit_bdd = bdd!(unit_bdd & (monotonicity & observability));
+            let restricted = bdd!(unit_bdd & (!non_monotonous));
+            if restricted.is_false() {
+                violations.push(ConstraintViolation {
+                    regulation: regulation.clone(),
+                    constraint: RegulationConstraint::Monotonicity(monotonicity),
+                    counterexample: counterexample(
+                        network,
+                        context,
+                        regulation,
+                        &bdd!(unit_bdd & non_monotonous_inputs),
+                    ),
+                });
+            } else {
+                unit_bdd = restricted;
+            }
+        }
     }
 
-    if unit_bdd.is_false() {
-        Err(format!(
-            "No update functions satisfy given constraints: \n{}",
-            error_message
-        ))
-    } else {
+    if violations.is_empty() {
         Ok(unit_bdd)
+    } else {
+        Err(StaticConstraintError {
+            graph: network.as_graph().clone(),
+            violations,
+        })
+    }
+}
+
+/// **(internal)** Read the values of the target regulators (except for the regulator itself)
+/// from a satisfying valuation of the `witness`.
+fn counterexample(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+    regulation: &Regulation,
+    witness: &Bdd,
+) -> Vec<(VariableId, bool)> {
+    match witness.sat_witness() {
+        None => Vec::new(),
+        Some(valuation) => network
+            .regulators(regulation.target)
+            .into_iter()
+            .filter(|var| *var != regulation.regulator)
+            .map(|var| (var, valuation[context.state_variables[var.0]]))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::constraint_violating_network;
+    use crate::symbolic_async_graph::{SymbolicAsyncGraph, SymbolicContext};
+    use crate::{Monotonicity, RegulationConstraint, VariableId};
+
+    #[test]
+    fn explain_violated_constraints() {
+        let bn = constraint_violating_network();
+        let (a, b, c, t) = (VariableId(0), VariableId(1), VariableId(2), VariableId(3));
+        let context = SymbolicContext::new(&bn).unwrap();
+        let error = context.mk_regulation_constraints(&bn).unwrap_err();
+        let violations = error.get_violations();
+        assert_eq!(2, violations.len());
+
+        let monotonicity = &violations[0];
+        assert_eq!(a, monotonicity.get_regulation().get_regulator());
+        assert_eq!(t, monotonicity.get_regulation().get_target());
+        assert_eq!(
+            RegulationConstraint::Monotonicity(Monotonicity::Activation),
+            monotonicity.get_constraint()
+        );
+        // `a` can only decrease `t` when `c` is false.
+        assert!(monotonicity.get_counterexample().contains(&(c, false)));
+        let inputs: Vec<VariableId> = monotonicity
+            .get_counterexample()
+            .iter()
+            .map(|(var, _)| *var)
+            .collect();
+        assert_eq!(vec![b, c], inputs);
+
+        let observability = &violations[1];
+        assert_eq!(b, observability.get_regulation().get_regulator());
+        assert_eq!(
+            RegulationConstraint::Observability,
+            observability.get_constraint()
+        );
+
+        assert!(std::error::Error::source(&error).is_none());
+        let message = error.to_string();
+        assert!(message.contains(" - a not activating in t (e.g. for b = "));
+        assert!(message.contains("c = 0).\n"));
+        assert!(message.contains(" - b has no effect in t (e.g. for a = "));
+        assert_eq!(Err(message), SymbolicAsyncGraph::new(bn).map(|_| ()));
     }
 }
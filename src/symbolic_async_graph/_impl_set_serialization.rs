@@ -0,0 +1,357 @@
+use crate::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices, SymbolicContext,
+};
+use biodivine_lib_bdd::Bdd;
+use std::io::{Read, Write};
+
+/// Serialization of symbolic sets.
+///
+/// A serialized set starts with a header `kind:fingerprint:` which identifies the type of
+/// the set and the layout of the `SymbolicContext` (see `SymbolicContext::layout_fingerprint`).
+/// The header is followed by the `Bdd` of the set, either in the string format of `Bdd`
+/// (`to_serialized_string`), or in the binary format of `Bdd` (`write_as_bytes`). In the
+/// binary format, the header also contains the length of the `Bdd` data (`kind:fingerprint:
+/// length:`), so that multiple sets can be written into (and read from) the same stream.
+/// A set can be only loaded using a context with the same layout.
+impl GraphColors {
+    /// Write this set into the given `output` using a binary format.
+    pub fn write_as_bytes(
+        &self,
+        context: &SymbolicContext,
+        output: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        write_set_as_bytes(COLORS, &self.bdd, context, output)
+    }
+
+    /// Read a set from the given `input` written by `write_as_bytes`.
+    pub fn read_as_bytes(
+        context: &SymbolicContext,
+        input: &mut dyn Read,
+    ) -> Result<GraphColors, String> {
+        let bdd = read_set_as_bytes(COLORS, context, input)?;
+        Ok(GraphColors::new(bdd, context))
+    }
+
+    /// Convert this set into a string.
+    pub fn to_serialized_string(&self, context: &SymbolicContext) -> String {
+        set_to_string(COLORS, &self.bdd, context)
+    }
+
+    /// Read a set from a string created by `to_serialized_string`.
+    pub fn from_serialized_string(
+        context: &SymbolicContext,
+        data: &str,
+    ) -> Result<GraphColors, String> {
+        let bdd = set_from_string(COLORS, context, data)?;
+        Ok(GraphColors::new(bdd, context))
+    }
+}
+
+/// Serialization of symbolic sets (see `GraphColors`).
+impl GraphVertices {
+    /// Write this set into the given `output` using a binary format.
+    pub fn write_as_bytes(
+        &self,
+        context: &SymbolicContext,
+        output: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        write_set_as_bytes(VERTICES, &self.bdd, context, output)
+    }
+
+    /// Read a set from the given `input` written by `write_as_bytes`.
+    pub fn read_as_bytes(
+        context: &SymbolicContext,
+        input: &mut dyn Read,
+    ) -> Result<GraphVertices, String> {
+        let bdd = read_set_as_bytes(VERTICES, context, input)?;
+        Ok(GraphVertices::new(bdd, context))
+    }
+
+    /// Convert this set into a string.
+    pub fn to_serialized_string(&self, context: &SymbolicContext) -> String {
+        set_to_string(VERTICES, &self.bdd, context)
+    }
+
+    /// Read a set from a string created by `to_serialized_string`.
+    pub fn from_serialized_string(
+        context: &SymbolicContext,
+        data: &str,
+    ) -> Result<GraphVertices, String> {
+        let bdd = set_from_string(VERTICES, context, data)?;
+        Ok(GraphVertices::new(bdd, context))
+    }
+}
+
+/// Serialization of symbolic sets (see `GraphColors`).
+impl GraphColoredVertices {
+    /// Write this set into the given `output` using a binary format.
+    pub fn write_as_bytes(
+        &self,
+        context: &SymbolicContext,
+        output: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        write_set_as_bytes(COLORED_VERTICES, &self.bdd, context, output)
+    }
+
+    /// Read a set from the given `input` written by `write_as_bytes`.
+    pub fn read_as_bytes(
+        context: &SymbolicContext,
+        input: &mut dyn Read,
+    ) -> Result<GraphColoredVertices, String> {
+        let bdd = read_set_as_bytes(COLORED_VERTICES, context, input)?;
+        Ok(GraphColoredVertices::new(bdd, context))
+    }
+
+    /// Convert this set into a string.
+    pub fn to_serialized_string(&self, context: &SymbolicContext) -> String {
+        set_to_string(COLORED_VERTICES, &self.bdd, context)
+    }
+
+    /// Read a set from a string created by `to_serialized_string`.
+    pub fn from_serialized_string(
+        context: &SymbolicContext,
+        data: &str,
+    ) -> Result<GraphColoredVertices, String> {
+        let bdd = set_from_string(COLORED_VERTICES, context, data)?;
+        Ok(GraphColoredVertices::new(bdd, context))
+    }
+}
+
+/// **(internal)** Identifiers of the serialized set types.
+const COLORS: &str = "colors";
+const VERTICES: &str = "vertices";
+const COLORED_VERTICES: &str = "colored_vertices";
+
+/// **(internal)** Size of one `Bdd` node in the binary format of `Bdd`.
+const NODE_SIZE: usize = 10;
+
+/// **(internal)** Maximal length of the header of a set in the binary format.
+const MAX_HEADER_SIZE: usize = 64;
+
+/// **(internal)** Create the header of a serialized set.
+fn header(kind: &str, context: &SymbolicContext) -> String {
+    format!("{}:{:016x}:", kind, context.layout_fingerprint())
+}
+
+/// **(internal)** Remove the header from the serialized `data`, checking that it matches
+/// the expected set `kind` and the layout of the `context`.
+fn strip_header<'a>(
+    kind: &str,
+    context: &SymbolicContext,
+    data: &'a [u8],
+) -> Result<&'a [u8], String> {
+    let mut parts = data.splitn(3, |b| *b == b':');
+    let (found_kind, fingerprint, rest) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(k), Some(f), Some(r)) => (k, f, r),
+        _ => return Err("Missing set header.".to_string()),
+    };
+    if found_kind != kind.as_bytes() {
+        return Err(format!(
+            "Expected a serialized set of {}, found `{}`.",
+            kind,
+            String::from_utf8_lossy(found_kind)
+        ));
+    }
+    let expected = format!("{:016x}", context.layout_fingerprint());
+    if fingerprint != expected.as_bytes() {
+        return Err(format!(
+            "Symbolic context layout mismatch: expected {}, found `{}`.",
+            expected,
+            String::from_utf8_lossy(fingerprint)
+        ));
+    }
+    Ok(rest)
+}
+
+/// **(internal)** Write the `bdd` of a set, including its header and length.
+fn write_set_as_bytes(
+    kind: &str,
+    bdd: &Bdd,
+    context: &SymbolicContext,
+    output: &mut dyn Write,
+) -> Result<(), std::io::Error> {
+    let data = bdd.to_bytes();
+    output.write_all(header(kind, context).as_bytes())?;
+    output.write_all(format!("{}:", data.len()).as_bytes())?;
+    output.write_all(&data)
+}
+
+/// **(internal)** Read and validate a set written by `write_set_as_bytes`.
+///
+/// Only the data of this one set are consumed from the `input`.
+fn read_set_as_bytes(
+    kind: &str,
+    context: &SymbolicContext,
+    input: &mut dyn Read,
+) -> Result<Bdd, String> {
+    // The header ends with the third `:` (after the length of the data).
+    let mut header = Vec::new();
+    let mut byte = [0u8; 1];
+    while header.iter().filter(|b| **b == b':').count() < 3 {
+        if header.len() >= MAX_HEADER_SIZE {
+            return Err("Invalid set header.".to_string());
+        }
+        input.read_exact(&mut byte).map_err(|e| e.to_string())?;
+        header.push(byte[0]);
+    }
+    let length = strip_header(kind, context, &header)?;
+    let length = std::str::from_utf8(&length[..(length.len() - 1)])
+        .ok()
+        .and_then(|it| it.parse::<usize>().ok())
+        .ok_or_else(|| "Invalid BDD data size.".to_string())?;
+    if length == 0 || length % NODE_SIZE != 0 {
+        return Err("Invalid BDD data size.".to_string());
+    }
+    let mut data = Vec::new();
+    input
+        .take(length as u64)
+        .read_to_end(&mut data)
+        .map_err(|e| e.to_string())?;
+    if data.len() != length {
+        return Err("Unexpected end of BDD data.".to_string());
+    }
+    let bdd = Bdd::read_as_bytes(&mut &data[..]).map_err(|e| e.to_string())?;
+    // Validate the loaded nodes using the string format of `Bdd`.
+    check_bdd_string(context, &bdd.to_string())?;
+    Ok(bdd)
+}
+
+/// **(internal)** Convert the `bdd` of a set into a string, including its header.
+fn set_to_string(kind: &str, bdd: &Bdd, context: &SymbolicContext) -> String {
+    format!("{}{}", header(kind, context), bdd)
+}
+
+/// **(internal)** Read and validate a set written by `set_to_string`.
+fn set_from_string(kind: &str, context: &SymbolicContext, data: &str) -> Result<Bdd, String> {
+    let nodes = strip_header(kind, context, data.as_bytes())?;
+    let nodes = std::str::from_utf8(nodes).map_err(|e| e.to_string())?;
+    check_bdd_string(context, nodes)?;
+    Ok(Bdd::from_string(nodes))
+}
+
+/// **(internal)** Check that `data` (in the string format of `Bdd`) encode a valid `Bdd`
+/// over the variables of the `context`.
+fn check_bdd_string(context: &SymbolicContext, data: &str) -> Result<(), String> {
+    let num_vars = context.bdd.num_vars();
+    let nodes = data
+        .split('|')
+        .filter(|it| !it.is_empty())
+        .map(|node| {
+            let invalid = || format!("Invalid BDD node `{}`.", node);
+            let items: Vec<&str> = node.split(',').collect();
+            if items.len() != 3 {
+                return Err(invalid());
+            }
+            let var = items[0].parse::<u16>().map_err(|_| invalid())?;
+            let low = items[1].parse::<usize>().map_err(|_| invalid())?;
+            let high = items[2].parse::<usize>().map_err(|_| invalid())?;
+            Ok((var, low, high))
+        })
+        .collect::<Result<Vec<(u16, usize, usize)>, String>>()?;
+    if nodes.is_empty() {
+        return Err("Empty BDD data.".to_string());
+    }
+    for (i, (var, low, high)) in nodes.iter().enumerate() {
+        let is_valid = if i < 2 {
+            // Terminal nodes.
+            *var == num_vars && *low == i && *high == i
+        } else {
+            *var < num_vars
+                && *low < i
+                && *high < i
+                && low != high
+                && nodes[*low].0 > *var
+                && nodes[*high].0 > *var
+        };
+        if !is_valid {
+            return Err(format!("Invalid BDD node at index {}.", i));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbolic_async_graph::{
+        GraphColoredVertices, GraphColors, GraphVertices, SymbolicAsyncGraph,
+    };
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn serialize_sets() {
+        // Both explicit and implicit parameters are part of the serialized layout.
+        let bn = BooleanNetwork::try_from("a -> b \n c -| b \n b -?? a \n $a: f(b)").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        let ctx = stg.symbolic_context();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let set = stg
+            .fix_network_variable(a, true)
+            .intersect_colors(&stg.unit_colors().pick_singleton());
+        let colors = set.colors();
+        let vertices = set.vertices();
+
+        // String format.
+        let loaded =
+            GraphColoredVertices::from_serialized_string(ctx, &set.to_serialized_string(ctx));
+        assert_eq!(set, loaded.unwrap());
+        let loaded = GraphColors::from_serialized_string(ctx, &colors.to_serialized_string(ctx));
+        assert_eq!(colors, loaded.unwrap());
+        let loaded =
+            GraphVertices::from_serialized_string(ctx, &vertices.to_serialized_string(ctx));
+        assert_eq!(vertices, loaded.unwrap());
+        let empty = stg.mk_empty_vertices();
+        let loaded =
+            GraphColoredVertices::from_serialized_string(ctx, &empty.to_serialized_string(ctx));
+        assert_eq!(empty, loaded.unwrap());
+
+        // Binary format.
+        let mut buffer = Vec::new();
+        set.write_as_bytes(ctx, &mut buffer).unwrap();
+        assert_eq!(
+            set,
+            GraphColoredVertices::read_as_bytes(ctx, &mut &buffer[..]).unwrap()
+        );
+        let mut buffer = Vec::new();
+        colors.write_as_bytes(ctx, &mut buffer).unwrap();
+        assert_eq!(
+            colors,
+            GraphColors::read_as_bytes(ctx, &mut &buffer[..]).unwrap()
+        );
+        let mut buffer = Vec::new();
+        vertices.write_as_bytes(ctx, &mut buffer).unwrap();
+        assert_eq!(
+            vertices,
+            GraphVertices::read_as_bytes(ctx, &mut &buffer[..]).unwrap()
+        );
+
+        // A graph of the same network can load the set.
+        let other = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        let data = set.to_serialized_string(ctx);
+        let loaded = GraphColoredVertices::from_serialized_string(other.symbolic_context(), &data);
+        assert_eq!(set, loaded.unwrap());
+
+        // Different layout, wrong set type or corrupted data are rejected.
+        let extra = SymbolicAsyncGraph::with_extra_state_variables(bn, 1).unwrap();
+        assert!(
+            GraphColoredVertices::from_serialized_string(extra.symbolic_context(), &data).is_err()
+        );
+        assert!(GraphColors::from_serialized_string(ctx, &data).is_err());
+        assert!(
+            GraphColoredVertices::from_serialized_string(ctx, &data[..(data.len() - 3)]).is_err()
+        );
+        assert!(GraphColoredVertices::from_serialized_string(ctx, "colored_vertices:").is_err());
+        assert!(GraphColoredVertices::read_as_bytes(ctx, &mut &buffer[..5]).is_err());
+        assert!(GraphVertices::read_as_bytes(ctx, &mut &buffer[..(buffer.len() - 1)]).is_err());
+
+        // Multiple sets can be read from the same stream.
+        let mut buffer = Vec::new();
+        set.write_as_bytes(ctx, &mut buffer).unwrap();
+        colors.write_as_bytes(ctx, &mut buffer).unwrap();
+        let mut input = &buffer[..];
+        let loaded = GraphColoredVertices::read_as_bytes(ctx, &mut input).unwrap();
+        assert_eq!(set, loaded);
+        assert_eq!(colors, GraphColors::read_as_bytes(ctx, &mut input).unwrap());
+        assert!(input.is_empty());
+    }
+}
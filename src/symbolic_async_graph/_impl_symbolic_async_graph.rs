@@ -8,9 +8,66 @@ use crate::{BooleanNetwork, FnUpdate, VariableId};
 use biodivine_lib_bdd::{bdd, BddVariable};
 
 impl SymbolicAsyncGraph {
+    /// Create a graph of the asynchronous transition system of the given `network`.
+    ///
+    /// If no update functions satisfy the static constraints of the network regulations,
+    /// the error explains the violated constraints. Use
+    /// `SymbolicContext::mk_regulation_constraints` to obtain them as a `StaticConstraintError`.
     pub fn new(network: BooleanNetwork) -> Result<SymbolicAsyncGraph, String> {
-        let context = SymbolicContext::new(&network)?;
-        let unit_bdd = apply_regulation_constraints(context.bdd.mk_true(), &network, &context)?;
+        SymbolicAsyncGraph::with_extra_state_variables(network, 0)
+    }
+
+    /// Create a graph whose symbolic context contains `extra` additional BDD variables for
+    /// every state variable (see `SymbolicContext::with_extra_state_variables`).
+    ///
+    /// The graph operators ignore the extra variables, i.e. they are preserved by `post`/`pre`.
+    pub fn with_extra_state_variables(
+        network: BooleanNetwork,
+        extra: u16,
+    ) -> Result<SymbolicAsyncGraph, String> {
+        let context = SymbolicContext::with_extra_state_variables(&network, extra)?;
+        SymbolicAsyncGraph::with_symbolic_context(network, context)
+    }
+
+    /// Create a graph using a custom `SymbolicContext` (e.g. one with named extra variables,
+    /// see `SymbolicContext::with_extra_variables`).
+    ///
+    /// The `context` must be created for the given `network`.
+    pub fn with_symbolic_context(
+        network: BooleanNetwork,
+        context: SymbolicContext,
+    ) -> Result<SymbolicAsyncGraph, String> {
+        let expected = SymbolicContext::new(&network)?;
+        let state_names = |ctx: &SymbolicContext| -> Vec<String> {
+            ctx.state_variables
+                .iter()
+                .map(|var| ctx.bdd.name_of(*var))
+                .collect()
+        };
+        let parameter_names = |ctx: &SymbolicContext| -> Vec<String> {
+            ctx.parameter_variables
+                .iter()
+                .map(|var| ctx.bdd.name_of(*var))
+                .collect()
+        };
+        if state_names(&expected) != state_names(&context)
+            || parameter_names(&expected) != parameter_names(&context)
+        {
+            return Err("The symbolic context does not match the network.".to_string());
+        }
+        let perturbations = vec![None; network.num_vars()];
+        SymbolicAsyncGraph::build(network, context, perturbations)
+    }
+
+    /// **(internal)** Create the graph from a validated `context`. The update function of every
+    /// variable with a perturbation variable is disabled when the perturbation variable is true.
+    pub(super) fn build(
+        network: BooleanNetwork,
+        context: SymbolicContext,
+        perturbations: Vec<Option<BddVariable>>,
+    ) -> Result<SymbolicAsyncGraph, String> {
+        let unit_bdd = apply_regulation_constraints(context.bdd.mk_true(), &network, &context)
+            .map_err(|error| error.to_string())?;
 
         // For each variable, pre-compute contexts where the update function can be applied, i.e.
         // (F = 1 & var = 0) | (F = 0 & var = 1)
@@ -25,7 +82,11 @@ impl SymbolicAsyncGraph {
                     .map(|fun| context.mk_fn_update_true(fun))
                     .unwrap_or_else(|| context.mk_implicit_function_is_true(variable, &regulators));
                 let variable_is_zero = context.mk_state_variable_is_true(variable).not();
-                bdd!(variable_is_zero <=> function_is_one)
+                let can_update = bdd!(variable_is_zero <=> function_is_one);
+                match perturbations[variable.0] {
+                    Some(perturbed) => can_update.and(&context.bdd.mk_not_var(perturbed)),
+                    None => can_update,
+                }
             })
             .collect();
 
@@ -42,6 +103,7 @@ impl SymbolicAsyncGraph {
             unit_bdd,
             network,
             update_functions,
+            perturbations,
         })
     }
 }
@@ -68,6 +130,8 @@ impl SymbolicAsyncGraph {
     }
 
     /// Make a witness network for one color in the given set.
+    ///
+    /// The perturbation variables of the color (if any) do not influence the witness.
     pub fn pick_witness(&self, colors: &GraphColors) -> BooleanNetwork {
         if colors.is_empty() {
             panic!("Cannot create witness for empty color set.");
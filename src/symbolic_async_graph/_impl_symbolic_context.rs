@@ -1,4 +1,4 @@
-use crate::symbolic_async_graph::{FunctionTable, SymbolicContext};
+use crate::symbolic_async_graph::{ExtraVariablePosition, FunctionTable, SymbolicContext};
 use crate::{BinaryOp, BooleanNetwork, FnUpdate, ParameterId, VariableId};
 use biodivine_lib_bdd::op_function::{and, and_not};
 use biodivine_lib_bdd::{
@@ -9,8 +9,55 @@ use std::convert::TryInto;
 impl SymbolicContext {
     /// Create a new `SymbolicContext` that is based on the given `BooleanNetwork`.
     pub fn new(network: &BooleanNetwork) -> Result<SymbolicContext, String> {
+        SymbolicContext::with_extra_state_variables(network, 0)
+    }
+
+    /// Create a new `SymbolicContext` that is based on the given `BooleanNetwork`, with
+    /// `extra` additional BDD variables for every state variable of the network.
+    ///
+    /// The extra variables directly follow their state variable in the BDD variable ordering.
+    /// They can be used to represent another copy of the state (e.g. the successor state of
+    /// a transition). Sets of vertices and colors do not depend on the extra variables.
+    pub fn with_extra_state_variables(
+        network: &BooleanNetwork,
+        extra: u16,
+    ) -> Result<SymbolicContext, String> {
+        SymbolicContext::with_extra_variables(network, extra, &[])
+    }
+
+    /// Create a new `SymbolicContext` that is based on the given `BooleanNetwork`, with
+    /// `extra_state` additional BDD variables for every state variable of the network
+    /// (see `with_extra_state_variables`) and a list of named `extra` BDD variables.
+    ///
+    /// Each named variable is placed at the given `ExtraVariablePosition`. Variables with
+    /// the same position follow the order in which they are listed. Names of extra variables
+    /// can only contain alphanumeric characters and `_`, and must be different from the names
+    /// of the network variables.
+    pub fn with_extra_variables(
+        network: &BooleanNetwork,
+        extra_state: u16,
+        extra: &[(&str, ExtraVariablePosition)],
+    ) -> Result<SymbolicContext, String> {
+        for (i, (name, position)) in extra.iter().enumerate() {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(format!("Invalid extra variable name `{}`.", name));
+            }
+            if network.as_graph().find_variable(name).is_some()
+                || extra[..i].iter().any(|(other, _)| other == name)
+            {
+                return Err(format!("Duplicate extra variable name `{}`.", name));
+            }
+            if let ExtraVariablePosition::AfterStateVariable(var) = position {
+                if var.0 >= network.num_vars() {
+                    return Err(format!("Unknown network variable {:?}.", var));
+                }
+            }
+        }
+
         // First, check if the network can be encoded using u16::MAX symbolic variables:
-        let symbolic_size = network_symbolic_size(network);
+        let extra_size = (network.num_vars() as u32).saturating_mul(u32::from(extra_state));
+        let extra_size = extra_size.saturating_add(extra.len() as u32);
+        let symbolic_size = network_symbolic_size(network).saturating_add(extra_size);
         if symbolic_size >= u32::from(u16::MAX) {
             return Err(format!(
                 "The network is too large. {} symbolic variables needed, but {} available.",
@@ -31,6 +78,14 @@ impl SymbolicContext {
         // in the network, since this should make things easier as well...
 
         let mut state_variables: Vec<BddVariable> = Vec::new();
+        let mut extra_variables: Vec<(String, BddVariable)> = Vec::new();
+        let mut make_extra_variables = |position, builder: &mut BddVariableSetBuilder| {
+            for (name, _) in extra.iter().filter(|(_, p)| *p == position) {
+                extra_variables.push((name.to_string(), builder.make_variable(name)));
+            }
+        };
+        make_extra_variables(ExtraVariablePosition::First, &mut builder);
+        let mut extra_state_variables: Vec<Vec<BddVariable>> = Vec::new();
         let mut implicit_function_tables: Vec<Option<FunctionTable>> =
             vec![None; network.num_vars()];
         let mut explicit_function_tables: Vec<Option<FunctionTable>> =
@@ -40,6 +95,12 @@ impl SymbolicContext {
             let variable_name = network[variable].get_name();
             let state_variable = builder.make_variable(variable_name);
             state_variables.push(state_variable);
+            let extra_variables = (0..extra_state)
+                .map(|i| builder.make_variable(&format!("{}'{}", variable_name, i)))
+                .collect();
+            extra_state_variables.push(extra_variables);
+            let position = ExtraVariablePosition::AfterStateVariable(variable);
+            make_extra_variables(position, &mut builder);
             if let Some(update_function) = network.get_update_function(variable) {
                 // For explicit function, go through all parameters used in the function.
                 for parameter in update_function.collect_parameters() {
@@ -61,6 +122,8 @@ impl SymbolicContext {
             }
         }
 
+        make_extra_variables(ExtraVariablePosition::Last, &mut builder);
+
         // Check that all parameter tables are constructed - if not, raise integrity error.
         for i_p in 0..network.num_parameters() {
             if explicit_function_tables[i_p].is_none() {
@@ -91,6 +154,8 @@ impl SymbolicContext {
         Ok(SymbolicContext {
             bdd: builder.build(),
             state_variables,
+            extra_state_variables,
+            extra_variables,
             parameter_variables,
             explicit_function_tables,
             implicit_function_tables,
@@ -102,11 +167,53 @@ impl SymbolicContext {
         &self.bdd
     }
 
+    /// Compute a fingerprint of the BDD variable layout of this context (the names and
+    /// the order of all BDD variables).
+    ///
+    /// Two contexts with the same fingerprint can share symbolic sets.
+    pub fn layout_fingerprint(&self) -> u64 {
+        // A 64-bit FNV-1a hash, which is stable across platforms and compiler versions.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for var in self.bdd.variables() {
+            for byte in self.bdd.name_of(var).bytes().chain(std::iter::once(0)) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
     /// Getter for variables encoding the state variables of the network.
     pub fn state_variables(&self) -> &Vec<BddVariable> {
         &self.state_variables
     }
 
+    /// Getter for the extra BDD variables of the given network `variable`.
+    pub fn extra_state_variables(&self, variable: VariableId) -> &Vec<BddVariable> {
+        &self.extra_state_variables[variable.0]
+    }
+
+    /// Collect the extra BDD variables at the given `offset` for all network variables.
+    pub fn all_extra_state_variables(&self, offset: usize) -> Vec<BddVariable> {
+        self.extra_state_variables
+            .iter()
+            .map(|extra| extra[offset])
+            .collect()
+    }
+
+    /// Getter for the named extra BDD variables, in the order in which they were declared.
+    pub fn extra_variables(&self) -> Vec<BddVariable> {
+        self.extra_variables.iter().map(|(_, var)| *var).collect()
+    }
+
+    /// Find the named extra BDD variable with the given `name`.
+    pub fn find_extra_variable(&self, name: &str) -> Option<BddVariable> {
+        self.extra_variables
+            .iter()
+            .find(|(extra_name, _)| extra_name == name)
+            .map(|(_, var)| *var)
+    }
+
     /// Getter for the entire function table of an implicit update function.
     pub fn get_implicit_function_table(&self, variable: VariableId) -> &FunctionTable {
         let table = &self.implicit_function_tables[variable.0];
@@ -143,6 +250,71 @@ impl SymbolicContext {
         self.bdd.mk_var(self.state_variables[variable.0])
     }
 
+    /// Create a `Bdd` that is true when the extra variable at the given `offset` of the network
+    /// `variable` is true.
+    pub fn mk_extra_state_variable_is_true(&self, variable: VariableId, offset: usize) -> Bdd {
+        self.bdd
+            .mk_var(self.extra_state_variables[variable.0][offset])
+    }
+
+    /// Move the dependence of `bdd` from the state variables to the extra state variables
+    /// at the given `offset`.
+    ///
+    /// The `bdd` must not depend on the extra state variables at the given `offset`, otherwise
+    /// the result is not correct. Use `swap_state_and_extra` if this is not the case.
+    pub fn transfer_state_to_extra(&self, bdd: &Bdd, offset: usize) -> Bdd {
+        let mapping = self
+            .state_variables
+            .iter()
+            .zip(self.extra_state_variables.iter())
+            .map(|(state, extra)| (*state, extra[offset]));
+        rename_variables(&self.bdd, bdd, mapping)
+    }
+
+    /// Inverse of `transfer_state_to_extra`: move the dependence of `bdd` from the extra state
+    /// variables at the given `offset` back to the state variables.
+    ///
+    /// The `bdd` must not depend on the state variables, otherwise the result is not correct.
+    pub fn transfer_extra_to_state(&self, bdd: &Bdd, offset: usize) -> Bdd {
+        let mapping = self
+            .state_variables
+            .iter()
+            .zip(self.extra_state_variables.iter())
+            .map(|(state, extra)| (extra[offset], *state));
+        rename_variables(&self.bdd, bdd, mapping)
+    }
+
+    /// Swap the values of the state variables and the extra state variables at the given
+    /// `offset` in the given `bdd`.
+    ///
+    /// Unlike `transfer_state_to_extra`, this works for any `bdd`, but is also slower, since
+    /// it has to rebuild the `bdd` from four cofactors for every variable pair.
+    pub fn swap_state_and_extra(&self, bdd: &Bdd, offset: usize) -> Bdd {
+        let pairs = self
+            .state_variables
+            .iter()
+            .zip(self.extra_state_variables.iter())
+            .map(|(state, extra)| (*state, extra[offset]));
+        let mut result = bdd.clone();
+        for (state, extra) in pairs {
+            let mut swapped = self.bdd.mk_false();
+            for (s, e) in &[(false, false), (false, true), (true, false), (true, true)] {
+                // Take the cofactor for `state = s, extra = e` and place it at `state = e,
+                // extra = s`.
+                let cofactor = result
+                    .select(&[(state, *s), (extra, *e)])
+                    .project(&[state, extra]);
+                let target = self
+                    .bdd
+                    .mk_literal(state, *e)
+                    .and(&self.bdd.mk_literal(extra, *s));
+                swapped = swapped.or(&cofactor.and(&target));
+            }
+            result = swapped;
+        }
+        result
+    }
+
     /// Create a `Bdd` that is true when given explicit uninterpreted function (aka parameter)
     /// is true for given arguments.
     pub fn mk_uninterpreted_function_is_true(
@@ -296,6 +468,21 @@ impl SymbolicContext {
     }
 }
 
+/// **(internal)** Rename the variables of the `bdd` according to the given `mapping`.
+///
+/// Every variable is renamed by conjunction with `from <=> to` and projection of `from`,
+/// so the new variables must not already appear in the `bdd` (and no variable can be renamed
+/// to a variable that is renamed later).
+fn rename_variables<I>(variables: &BddVariableSet, bdd: &Bdd, mapping: I) -> Bdd
+where
+    I: Iterator<Item = (BddVariable, BddVariable)>,
+{
+    mapping.fold(bdd.clone(), |result, (from, to)| {
+        let rename = variables.mk_var(from).iff(&variables.mk_var(to));
+        result.and(&rename).var_project(from)
+    })
+}
+
 /// **(internal)** Compute the number of rows necessary to represent a function with given arity.
 fn arity_to_row_count(arity: u32) -> u32 {
     1u32.checked_shl(arity).unwrap_or(u32::MAX)
@@ -328,8 +515,8 @@ fn network_symbolic_size(network: &BooleanNetwork) -> u32 {
 #[cfg(test)]
 mod tests {
     use crate::biodivine_std::traits::Set;
-    use crate::symbolic_async_graph::SymbolicAsyncGraph;
-    use crate::BooleanNetwork;
+    use crate::symbolic_async_graph::{ExtraVariablePosition, SymbolicAsyncGraph, SymbolicContext};
+    use crate::{BooleanNetwork, VariableId};
     use std::convert::TryFrom;
 
     #[test]
@@ -339,4 +526,100 @@ mod tests {
         let graph = SymbolicAsyncGraph::new(network).unwrap();
         assert!(!graph.unit_colored_vertices().is_empty());
     }
+
+    #[test]
+    fn named_extra_variables() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            $a: !b
+        ",
+        )
+        .unwrap();
+        let a = VariableId(0);
+        let extra = [
+            ("last", ExtraVariablePosition::Last),
+            ("after_a", ExtraVariablePosition::AfterStateVariable(a)),
+            ("first", ExtraVariablePosition::First),
+            ("after_a_2", ExtraVariablePosition::AfterStateVariable(a)),
+        ];
+        let ctx = SymbolicContext::with_extra_variables(&bn, 1, &extra).unwrap();
+        let names: Vec<String> = ctx
+            .bdd_variable_set()
+            .variables()
+            .into_iter()
+            .map(|var| ctx.bdd_variable_set().name_of(var))
+            .collect();
+        let expected = vec![
+            "first",
+            "a",
+            "a'0",
+            "after_a",
+            "after_a_2",
+            "b",
+            "b'0",
+            "f_b[0]",
+            "f_b[1]",
+            "last",
+        ];
+        assert_eq!(expected, names);
+        assert_eq!(4, ctx.extra_variables().len());
+        assert_eq!(
+            Some(ctx.extra_variables()[1]),
+            ctx.find_extra_variable("after_a")
+        );
+        assert_eq!(None, ctx.find_extra_variable("a"));
+
+        // The graph works with the custom context and ignores the extra variables.
+        let graph = SymbolicAsyncGraph::with_symbolic_context(bn.clone(), ctx).unwrap();
+        let default = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        assert_eq!(
+            default.unit_colored_vertices().approx_cardinality(),
+            graph.unit_colored_vertices().approx_cardinality()
+        );
+        let other = BooleanNetwork::try_from("a -> b \n b -> a").unwrap();
+        let other = SymbolicContext::new(&other).unwrap();
+        assert!(SymbolicAsyncGraph::with_symbolic_context(bn.clone(), other).is_err());
+
+        let invalid = vec![
+            vec![("a", ExtraVariablePosition::First)],
+            vec![("x y", ExtraVariablePosition::First)],
+            vec![("", ExtraVariablePosition::Last)],
+            vec![
+                ("x", ExtraVariablePosition::First),
+                ("x", ExtraVariablePosition::Last),
+            ],
+            vec![(
+                "x",
+                ExtraVariablePosition::AfterStateVariable(VariableId(2)),
+            )],
+        ];
+        for extra in invalid {
+            assert!(SymbolicContext::with_extra_variables(&bn, 0, &extra).is_err());
+        }
+    }
+
+    #[test]
+    fn rename_and_swap_extra_state_variables() {
+        let bn = BooleanNetwork::try_from("a -> b \n b -| a").unwrap();
+        let ctx = SymbolicContext::with_extra_state_variables(&bn, 2).unwrap();
+        let (a, b) = (VariableId(0), VariableId(1));
+        let a_state = ctx.mk_state_variable_is_true(a);
+        let b_state = ctx.mk_state_variable_is_true(b);
+        let a_extra = ctx.mk_extra_state_variable_is_true(a, 1);
+        let b_extra = ctx.mk_extra_state_variable_is_true(b, 1);
+
+        let state = a_state.and_not(&b_state);
+        let extra = a_extra.and_not(&b_extra);
+        assert_eq!(extra, ctx.transfer_state_to_extra(&state, 1));
+        assert_eq!(state, ctx.transfer_extra_to_state(&extra, 1));
+
+        // Swap works even when both copies are used.
+        let relation = a_state.and(&b_extra.not()).or(&b_state.iff(&a_extra));
+        let swapped = a_extra.and(&b_state.not()).or(&b_extra.iff(&a_state));
+        assert_eq!(swapped, ctx.swap_state_and_extra(&relation, 1));
+        assert_eq!(relation, ctx.swap_state_and_extra(&swapped, 1));
+        assert_eq!(extra, ctx.swap_state_and_extra(&state, 1));
+    }
 }
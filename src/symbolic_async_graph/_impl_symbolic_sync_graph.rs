@@ -0,0 +1,341 @@
+use crate::biodivine_std::bitvector::ArrayBitVector;
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, SymbolicAsyncGraph, SymbolicContext, SymbolicSyncGraph,
+    UpdateScheme,
+};
+use crate::{BooleanNetwork, VariableId};
+use biodivine_lib_bdd::BddVariable;
+use std::cmp::max;
+
+impl SymbolicSyncGraph {
+    /// Create a graph of the synchronous transition system of the given `network`.
+    ///
+    /// Same as `SymbolicAsyncGraph::new`, the error explains the violated static constraints
+    /// of the network regulations (see `SymbolicContext::mk_regulation_constraints`).
+    pub fn new(network: BooleanNetwork) -> Result<SymbolicSyncGraph, String> {
+        Self::with_update_scheme(network, UpdateScheme::Synchronous)
+    }
+
+    /// Create a graph of the transition system of the given `network` under the given
+    /// `update_scheme`.
+    pub fn with_update_scheme(
+        network: BooleanNetwork,
+        update_scheme: UpdateScheme,
+    ) -> Result<SymbolicSyncGraph, String> {
+        let graph = SymbolicAsyncGraph::with_extra_state_variables(network, 1)?;
+        let update_choices =
+            update_scheme.update_choices(graph.as_network(), graph.symbolic_context())?;
+        Ok(SymbolicSyncGraph {
+            graph,
+            update_scheme,
+            update_choices,
+        })
+    }
+}
+
+/// Examine the general properties of the graph.
+///
+/// The state space is shared with the asynchronous graph of the same symbolic context,
+/// hence these methods are the same as in `SymbolicAsyncGraph`.
+impl SymbolicSyncGraph {
+    /// Return a reference to the original Boolean network.
+    pub fn as_network(&self) -> &BooleanNetwork {
+        self.graph.as_network()
+    }
+
+    /// Return the update scheme used by this graph.
+    pub fn update_scheme(&self) -> &UpdateScheme {
+        &self.update_scheme
+    }
+
+    /// Return a reference to the symbolic context of this graph.
+    pub fn symbolic_context(&self) -> &SymbolicContext {
+        self.graph.symbolic_context()
+    }
+
+    /// Create a colored vertex set with a fixed value of the given variable.
+    pub fn fix_network_variable(&self, variable: VariableId, value: bool) -> GraphColoredVertices {
+        self.graph.fix_network_variable(variable, value)
+    }
+
+    /// Reference to an empty color set.
+    pub fn empty_colors(&self) -> &GraphColors {
+        self.graph.empty_colors()
+    }
+
+    /// Make a new copy of empty color set.
+    pub fn mk_empty_colors(&self) -> GraphColors {
+        self.graph.mk_empty_colors()
+    }
+
+    /// Reference to a unit color set.
+    pub fn unit_colors(&self) -> &GraphColors {
+        self.graph.unit_colors()
+    }
+
+    /// Make a new copy of unit color set.
+    pub fn mk_unit_colors(&self) -> GraphColors {
+        self.graph.mk_unit_colors()
+    }
+
+    /// Reference to an empty colored vertex set.
+    pub fn empty_vertices(&self) -> &GraphColoredVertices {
+        self.graph.empty_vertices()
+    }
+
+    /// Make a new copy of empty vertex set.
+    pub fn mk_empty_vertices(&self) -> GraphColoredVertices {
+        self.graph.mk_empty_vertices()
+    }
+
+    /// Reference to a unit colored vertex set.
+    pub fn unit_colored_vertices(&self) -> &GraphColoredVertices {
+        self.graph.unit_colored_vertices()
+    }
+
+    /// Make a new copy of unit vertex set.
+    pub fn mk_unit_colored_vertices(&self) -> GraphColoredVertices {
+        self.graph.mk_unit_colored_vertices()
+    }
+
+    /// Construct a vertex set that only contains one vertex, but all colors
+    pub fn vertex(&self, state: &ArrayBitVector) -> GraphColoredVertices {
+        self.graph.vertex(state)
+    }
+}
+
+/// Symbolic successors and predecessors with respect to the update scheme of the graph.
+impl SymbolicSyncGraph {
+    /// Compute the set of successors of the given `initial` set.
+    ///
+    /// The state variables are eliminated as soon as they are not needed by the remaining
+    /// update relations (i.e. after their last target is processed).
+    pub fn post(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        let context = self.symbolic_context();
+        let network = self.as_network();
+        let variables: Vec<VariableId> = network.variables().collect();
+        // For every variable, the last (in network order) update relation which depends on it.
+        let mut last_use: Vec<usize> = variables.iter().map(|v| v.0).collect();
+        for (i, target) in variables.iter().enumerate() {
+            for regulator in network.regulators(*target) {
+                last_use[regulator.0] = max(last_use[regulator.0], i);
+            }
+        }
+
+        let mut result = context.bdd.mk_false();
+        for choice in &self.update_choices {
+            let mut image = initial.as_bdd().and(&choice.guard);
+            for (i, relation) in choice.relations.iter().enumerate() {
+                image = image.and(relation);
+                let finished: Vec<BddVariable> = variables
+                    .iter()
+                    .filter(|v| last_use[v.0] == i)
+                    .map(|v| context.state_variables[v.0])
+                    .collect();
+                image = image.project(&finished);
+            }
+            result = result.or(&image);
+        }
+        let result = context.transfer_extra_to_state(&result, 0);
+        GraphColoredVertices::new(result, context).intersect(self.unit_colored_vertices())
+    }
+
+    /// Compute the set of predecessors of the given `initial` set.
+    pub fn pre(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        let context = self.symbolic_context();
+        let successors = context.transfer_state_to_extra(initial.as_bdd(), 0);
+        let mut result = context.bdd.mk_false();
+        for choice in &self.update_choices {
+            let mut image = successors.clone();
+            for (i, relation) in choice.relations.iter().enumerate() {
+                // The successor value of variable i is only constrained by its own relation.
+                image = image
+                    .and(relation)
+                    .var_project(context.extra_state_variables[i][0]);
+            }
+            result = result.or(&image.and(&choice.guard));
+        }
+        GraphColoredVertices::new(result, context).intersect(self.unit_colored_vertices())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_test_util::tutorial_network;
+    use crate::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::{
+        PriorityClass, SymbolicAsyncGraph, SymbolicSyncGraph, UpdateScheme,
+    };
+    use crate::{BooleanNetwork, VariableId};
+    use std::convert::TryFrom;
+
+    fn state(index: usize) -> ArrayBitVector {
+        ArrayBitVector::from(vec![index & 1 != 0, index & 2 != 0, index & 4 != 0])
+    }
+
+    fn cycle_network() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            a -> c
+            b -> c
+            $a: !b
+            $b: !a
+            $c: a & b
+        ",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn synchronous_post_and_pre() {
+        let stg = SymbolicSyncGraph::new(cycle_network()).unwrap();
+        assert_eq!(8.0, stg.unit_colored_vertices().approx_cardinality());
+
+        // Check the successor of every state explicitly.
+        for i in 0..8 {
+            let (a, b) = (state(i).get(0), state(i).get(1));
+            let successor = ArrayBitVector::from(vec![!b, !a, a && b]);
+            let vertex = stg.vertex(&state(i));
+            assert_eq!(stg.vertex(&successor), stg.post(&vertex));
+            assert!(stg
+                .pre(&stg.vertex(&successor))
+                .is_subset(&stg.pre(&stg.post(&vertex))));
+            assert!(vertex.is_subset(&stg.pre(&stg.post(&vertex))));
+        }
+
+        // Synchronous and asynchronous fixed points are the same.
+        let async_stg = SymbolicAsyncGraph::new(cycle_network()).unwrap();
+        let mut fixed_points = async_stg.mk_unit_colored_vertices();
+        for var in async_stg.as_network().variables() {
+            fixed_points = fixed_points.minus(&async_stg.var_can_post(var, &fixed_points));
+        }
+        let sync_fixed_points = (0..8)
+            .map(|i| stg.vertex(&state(i)))
+            .filter(|vertex| stg.post(vertex) == *vertex)
+            .count();
+        assert_eq!(fixed_points.approx_cardinality(), sync_fixed_points as f64);
+    }
+
+    #[test]
+    fn synchronous_parametrised_post() {
+        let bn = tutorial_network();
+        let stg = SymbolicSyncGraph::new(bn).unwrap();
+        let unit = stg.unit_colored_vertices();
+        assert_eq!(unit.colors(), stg.post(unit).colors());
+        assert!(stg.post(unit).is_subset(unit));
+        // Every colored vertex has exactly one successor.
+        for i in 0..8 {
+            let vertex = stg.vertex(&state(i));
+            let successors = stg.post(&vertex);
+            assert_eq!(vertex.colors(), successors.colors());
+            assert_eq!(
+                successors.colors().approx_cardinality(),
+                successors.approx_cardinality()
+            );
+            assert!(vertex.is_subset(&stg.pre(&successors)));
+        }
+    }
+
+    #[test]
+    fn asynchronous_scheme_agrees_with_async_graph() {
+        let bn = tutorial_network();
+        let stg = SymbolicSyncGraph::with_update_scheme(bn.clone(), UpdateScheme::Asynchronous);
+        let stg = stg.unwrap();
+        let async_stg = SymbolicAsyncGraph::new(bn).unwrap();
+        for i in 0..8 {
+            let post = stg.post(&stg.vertex(&state(i)));
+            let pre = stg.pre(&stg.vertex(&state(i)));
+            let async_post = async_stg.post(&async_stg.vertex(&state(i)));
+            let async_pre = async_stg.pre(&async_stg.vertex(&state(i)));
+            for j in 0..8 {
+                let (vertex, async_vertex) = (stg.vertex(&state(j)), async_stg.vertex(&state(j)));
+                assert_eq!(
+                    post.intersect(&vertex).colors().approx_cardinality(),
+                    async_post
+                        .intersect(&async_vertex)
+                        .colors()
+                        .approx_cardinality()
+                );
+                assert_eq!(
+                    pre.intersect(&vertex).colors().approx_cardinality(),
+                    async_pre
+                        .intersect(&async_vertex)
+                        .colors()
+                        .approx_cardinality()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generalized_asynchronous_scheme() {
+        let scheme = UpdateScheme::GeneralizedAsynchronous;
+        let stg = SymbolicSyncGraph::with_update_scheme(cycle_network(), scheme).unwrap();
+        for i in 0..8 {
+            let source = state(i);
+            let (a, b) = (source.get(0), source.get(1));
+            let update = [!b, !a, a && b];
+            // Successors are all different states where every variable is either
+            // unchanged or updated.
+            let mut expected = stg.mk_empty_vertices();
+            for j in (0..8).filter(|j| *j != i) {
+                let target = state(j);
+                if (0..3).all(|k| target.get(k) == source.get(k) || target.get(k) == update[k]) {
+                    expected = expected.union(&stg.vertex(&target));
+                }
+            }
+            assert_eq!(expected, stg.post(&stg.vertex(&source)));
+            for j in 0..8 {
+                let target = stg.vertex(&state(j));
+                assert_eq!(
+                    target.is_subset(&stg.post(&stg.vertex(&source))),
+                    stg.vertex(&source).is_subset(&stg.pre(&target))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn priority_scheme() {
+        let (a, b, c) = (VariableId(0), VariableId(1), VariableId(2));
+        let classes = vec![
+            PriorityClass::asynchronous(vec![c]),
+            PriorityClass::synchronous(vec![a, b]),
+        ];
+        let scheme = UpdateScheme::Priority(classes);
+        let stg = SymbolicSyncGraph::with_update_scheme(cycle_network(), scheme).unwrap();
+        for i in 0..8 {
+            let source = state(i);
+            let (a, b, c) = (source.get(0), source.get(1), source.get(2));
+            let expected = if c != (a && b) {
+                // Variable c has priority whenever it can change.
+                stg.vertex(&ArrayBitVector::from(vec![a, b, !c]))
+            } else if a == b {
+                // Otherwise, a and b are updated synchronously (if one of them can change).
+                stg.vertex(&ArrayBitVector::from(vec![!b, !a, c]))
+            } else {
+                stg.mk_empty_vertices()
+            };
+            assert_eq!(expected, stg.post(&stg.vertex(&source)));
+            for j in 0..8 {
+                let target = stg.vertex(&state(j));
+                assert_eq!(
+                    target.is_subset(&stg.post(&stg.vertex(&source))),
+                    stg.vertex(&source).is_subset(&stg.pre(&target))
+                );
+            }
+        }
+
+        let missing = UpdateScheme::Priority(vec![PriorityClass::synchronous(vec![a, b])]);
+        assert!(SymbolicSyncGraph::with_update_scheme(cycle_network(), missing).is_err());
+        let duplicate = UpdateScheme::Priority(vec![
+            PriorityClass::synchronous(vec![a, b, c]),
+            PriorityClass::asynchronous(vec![a]),
+        ]);
+        assert!(SymbolicSyncGraph::with_update_scheme(cycle_network(), duplicate).is_err());
+    }
+}
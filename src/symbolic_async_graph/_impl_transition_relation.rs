@@ -0,0 +1,141 @@
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+use crate::VariableId;
+use biodivine_lib_bdd::Bdd;
+
+/// Explicit transition relation of the graph.
+///
+/// The relation is a `Bdd` over the state variables (source state), the extra state variables
+/// at some `offset` (target state, see `SymbolicAsyncGraph::with_extra_state_variables`) and
+/// the parameter variables. It is not stored in the graph, but computed on demand, since
+/// the basic graph operators do not need it.
+impl SymbolicAsyncGraph {
+    /// Build the transition relation of the update function of the given `variable`: the
+    /// `variable` changes its value (when its update function allows it) and all other
+    /// variables keep their value in the target state.
+    ///
+    /// Panic: The graph must have extra state variables at the given `offset`.
+    pub fn mk_variable_transition_relation(&self, variable: VariableId, offset: usize) -> Bdd {
+        let context = &self.symbolic_context;
+        self.check_transition_offset(offset);
+        let mut relation = self.unit_bdd.and(&self.update_functions[variable.0]);
+        for var in self.network.variables() {
+            let state = context.mk_state_variable_is_true(var);
+            let target = context.mk_extra_state_variable_is_true(var, offset);
+            let var_relation = if var == variable {
+                state.xor(&target)
+            } else {
+                state.iff(&target)
+            };
+            relation = relation.and(&var_relation);
+        }
+        relation
+    }
+
+    /// Build the transition relation of the whole graph, i.e. the union of all variable
+    /// transition relations (see `mk_variable_transition_relation`).
+    ///
+    /// Panic: The graph must have extra state variables at the given `offset`.
+    pub fn mk_transition_relation(&self, offset: usize) -> Bdd {
+        self.network
+            .variables()
+            .fold(self.symbolic_context.mk_constant(false), |result, var| {
+                result.or(&self.mk_variable_transition_relation(var, offset))
+            })
+    }
+
+    /// Compute the successors of the `initial` set using an explicit transition `relation`
+    /// (with target states at the given `offset`) using relational product.
+    ///
+    /// The result is the same as `post`, but the computation is typically slower.
+    pub fn relation_post(
+        &self,
+        relation: &Bdd,
+        initial: &GraphColoredVertices,
+        offset: usize,
+    ) -> GraphColoredVertices {
+        let context = &self.symbolic_context;
+        let successors = initial.bdd.and(relation).project(context.state_variables());
+        let successors = context.transfer_extra_to_state(&successors, offset);
+        GraphColoredVertices::new(successors.and(&self.unit_bdd), context)
+    }
+
+    /// Compute the predecessors of the `initial` set using an explicit transition `relation`
+    /// (with target states at the given `offset`) using relational product.
+    ///
+    /// The result is the same as `pre`, but the computation is typically slower.
+    pub fn relation_pre(
+        &self,
+        relation: &Bdd,
+        initial: &GraphColoredVertices,
+        offset: usize,
+    ) -> GraphColoredVertices {
+        let context = &self.symbolic_context;
+        let targets = context.transfer_state_to_extra(&initial.bdd, offset);
+        let predecessors = targets
+            .and(relation)
+            .project(&context.all_extra_state_variables(offset));
+        GraphColoredVertices::new(predecessors.and(&self.unit_bdd), context)
+    }
+
+    /// **(internal)** Check that the graph has extra state variables at the given `offset`.
+    fn check_transition_offset(&self, offset: usize) {
+        let extra = self
+            .network
+            .variables()
+            .next()
+            .map(|var| self.symbolic_context.extra_state_variables(var).len())
+            .unwrap_or(0);
+        if offset >= extra {
+            panic!(
+                "The graph has {} extra state variables, but offset {} was requested.",
+                extra, offset
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn transition_relation_agrees_with_operators() {
+        // The relation depends on the explicit parameter `f` and the implicit function of `c`.
+        let bn = BooleanNetwork::try_from("a -> b \n b -| a \n c -? a \n $a: !b & f(c)").unwrap();
+        let stg = SymbolicAsyncGraph::with_extra_state_variables(bn, 2).unwrap();
+        let a = stg.as_network().as_graph().find_variable("a").unwrap();
+        let sets = vec![
+            stg.mk_unit_colored_vertices(),
+            stg.fix_network_variable(a, true),
+            stg.unit_colored_vertices().pick_vertex(),
+        ];
+        for offset in 0..2 {
+            let relation = stg.mk_transition_relation(offset);
+            for set in &sets {
+                assert_eq!(stg.post(set), stg.relation_post(&relation, set, offset));
+                assert_eq!(stg.pre(set), stg.relation_pre(&relation, set, offset));
+                for var in stg.as_network().variables() {
+                    let var_relation = stg.mk_variable_transition_relation(var, offset);
+                    let post = stg.relation_post(&var_relation, set, offset);
+                    let pre = stg.relation_pre(&var_relation, set, offset);
+                    assert_eq!(stg.var_post(var, set), post);
+                    assert_eq!(
+                        stg.var_pre(var, set).intersect(stg.unit_colored_vertices()),
+                        pre
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn transition_relation_needs_extra_variables() {
+        let bn = BooleanNetwork::try_from("a -> b \n b -| a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn).unwrap();
+        stg.mk_transition_relation(0);
+    }
+}
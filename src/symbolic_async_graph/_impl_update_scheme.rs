@@ -0,0 +1,160 @@
+use crate::symbolic_async_graph::{PriorityClass, SymbolicContext, UpdateChoice, UpdateScheme};
+use crate::{BooleanNetwork, VariableId};
+use biodivine_lib_bdd::Bdd;
+
+impl PriorityClass {
+    /// A class in which all `variables` are updated simultaneously.
+    pub fn synchronous(variables: Vec<VariableId>) -> PriorityClass {
+        PriorityClass {
+            variables,
+            synchronous: true,
+        }
+    }
+
+    /// A class in which one of the `variables` is updated in each step.
+    pub fn asynchronous(variables: Vec<VariableId>) -> PriorityClass {
+        PriorityClass {
+            variables,
+            synchronous: false,
+        }
+    }
+}
+
+impl UpdateScheme {
+    /// **(internal)** Build the transition relation of this scheme for the given `network`.
+    ///
+    /// The `context` must contain (at least) one extra variable for every state variable,
+    /// which represents the successor state.
+    pub(super) fn update_choices(
+        &self,
+        network: &BooleanNetwork,
+        context: &SymbolicContext,
+    ) -> Result<Vec<UpdateChoice>, String> {
+        let relations = VariableRelations::new(network, context);
+        let variables: Vec<VariableId> = network.variables().collect();
+        let choices = match self {
+            UpdateScheme::Synchronous => vec![UpdateChoice {
+                guard: context.bdd.mk_true(),
+                relations: relations.update.clone(),
+            }],
+            UpdateScheme::Asynchronous => variables
+                .iter()
+                .map(|var| relations.asynchronous(*var, context.bdd.mk_true()))
+                .collect(),
+            UpdateScheme::GeneralizedAsynchronous => {
+                // The k-th choice updates variable k and any subset of the remaining variables.
+                // Together, the choices cover every step which changes at least one variable.
+                variables
+                    .iter()
+                    .map(|var| {
+                        let relations = variables
+                            .iter()
+                            .map(|other| {
+                                if other == var {
+                                    relations.change[other.0].clone()
+                                } else {
+                                    relations.update[other.0].or(&relations.keep[other.0])
+                                }
+                            })
+                            .collect();
+                        UpdateChoice {
+                            guard: context.bdd.mk_true(),
+                            relations,
+                        }
+                    })
+                    .collect()
+            }
+            UpdateScheme::Priority(classes) => {
+                let mut seen = vec![false; variables.len()];
+                for var in classes.iter().flat_map(|class| class.variables.iter()) {
+                    if seen[var.0] {
+                        let name = network.get_variable_name(*var);
+                        return Err(format!("Variable {} has more than one priority.", name));
+                    }
+                    seen[var.0] = true;
+                }
+                if let Some(var) = variables.iter().find(|var| !seen[var.0]) {
+                    let name = network.get_variable_name(*var);
+                    return Err(format!("Variable {} has no priority.", name));
+                }
+
+                let mut choices = Vec::new();
+                // States in which some variable of a higher priority class can change.
+                let mut blocked = context.bdd.mk_false();
+                for class in classes {
+                    let enabled = class
+                        .variables
+                        .iter()
+                        .fold(context.bdd.mk_false(), |a, var| {
+                            a.or(&relations.enabled[var.0])
+                        });
+                    let guard = enabled.and_not(&blocked);
+                    if class.synchronous {
+                        let mut class_relations = relations.keep.clone();
+                        for var in &class.variables {
+                            class_relations[var.0] = relations.update[var.0].clone();
+                        }
+                        choices.push(UpdateChoice {
+                            guard,
+                            relations: class_relations,
+                        });
+                    } else {
+                        for var in &class.variables {
+                            choices.push(relations.asynchronous(*var, guard.clone()));
+                        }
+                    }
+                    blocked = blocked.or(&enabled);
+                }
+                choices
+            }
+        };
+        Ok(choices)
+    }
+}
+
+/// **(internal)** Basic relations between the current and successor value of every variable.
+struct VariableRelations {
+    // v' <=> v
+    keep: Vec<Bdd>,
+    // v' <=> F
+    update: Vec<Bdd>,
+    // v' <=> F & v' != v
+    change: Vec<Bdd>,
+    // v != F
+    enabled: Vec<Bdd>,
+}
+
+impl VariableRelations {
+    pub fn new(network: &BooleanNetwork, context: &SymbolicContext) -> VariableRelations {
+        let mut relations = VariableRelations {
+            keep: Vec::new(),
+            update: Vec::new(),
+            change: Vec::new(),
+            enabled: Vec::new(),
+        };
+        for variable in network.variables() {
+            let regulators = network.regulators(variable);
+            let function_is_one = network
+                .get_update_function(variable)
+                .as_ref()
+                .map(|fun| context.mk_fn_update_true(fun))
+                .unwrap_or_else(|| context.mk_implicit_function_is_true(variable, &regulators));
+            let state_is_one = context.mk_state_variable_is_true(variable);
+            let successor_is_one = context.mk_extra_state_variable_is_true(variable, 0);
+            let update = successor_is_one.iff(&function_is_one);
+            let flip = successor_is_one.xor(&state_is_one);
+            relations.change.push(update.and(&flip));
+            relations.keep.push(flip.not());
+            relations.update.push(update);
+            relations.enabled.push(state_is_one.xor(&function_is_one));
+        }
+        relations
+    }
+
+    /// A choice which changes only the given `variable`, assuming the `guard` holds.
+    pub fn asynchronous(&self, variable: VariableId, guard: Bdd) -> UpdateChoice {
+        let mut relations = self.keep.clone();
+        relations[variable.0] = self.change[variable.0].clone();
+        UpdateChoice { guard, relations }
+    }
+}
@@ -28,14 +28,22 @@
 //! any custom BDD operations, but it should be used with caution.
 //!
 
-use crate::BooleanNetwork;
+use crate::{BooleanNetwork, FnUpdate, VariableId};
 use biodivine_lib_bdd::{
     Bdd, BddSatisfyingValuations, BddValuationIterator, BddVariable, BddVariableSet,
 };
 use std::iter::Enumerate;
 
+/// **(internal)** Weak and strong basins of attraction.
+mod _impl_basins;
+/// **(internal)** Enumeration of witness networks and instantiated functions of colors.
+mod _impl_color_witnesses;
+/// **(internal)** Exact cardinalities of symbolic sets.
+pub(crate) mod _impl_exact_cardinality;
 /// **(internal)** Implementing conversion between `FnUpdate` and `BooleanExpression`.
 mod _impl_fn_update_from_boolean_expression;
+/// **(internal)** Projection of color sets onto the uninterpreted functions.
+mod _impl_function_projection;
 /// **(internal)** Implementation for `FunctionTable` and `FunctionTableIterator`.
 mod _impl_function_table;
 /// **(internal)** Implement set operations for `GraphColoredVertices`.
@@ -44,14 +52,28 @@ mod _impl_graph_colored_vertices;
 mod _impl_graph_colors;
 /// **(internal)** Implement set operations for `GraphVertices`.
 mod _impl_graph_vertices;
+/// **(internal)** Symbolic encoding of network perturbations.
+mod _impl_perturbations;
+/// **(internal)** Uniform random sampling of symbolic sets.
+mod _impl_random_sampling;
+/// **(internal)** Saturation-based reachability on the `SymbolicAsyncGraph`.
+mod _impl_reachability;
 /// **(internal)** Utility methods for validation of static constraints on network regulations.
-mod _impl_regulation_constraint;
+pub(crate) mod _impl_regulation_constraint;
+/// **(internal)** Serialization of `GraphColors`, `GraphVertices` and `GraphColoredVertices`.
+mod _impl_set_serialization;
 /// **(internal)** Utility methods for `SymbolicAsyncGraph`.
 mod _impl_symbolic_async_graph;
 /// **(internal)** Implement symbolic graph operators (pre/post/...).
 mod _impl_symbolic_async_graph_operators;
 /// **(internal)** Implementation of the `SymbolicContext`.
 mod _impl_symbolic_context;
+/// **(internal)** Utility methods and symbolic operators of `SymbolicSyncGraph`.
+mod _impl_symbolic_sync_graph;
+/// **(internal)** Explicit transition relation of the `SymbolicAsyncGraph`.
+mod _impl_transition_relation;
+/// **(internal)** Translation of an `UpdateScheme` into symbolic update relations.
+mod _impl_update_scheme;
 
 /// Symbolic representation of a color set.
 ///
@@ -95,6 +117,32 @@ pub struct GraphVertexIterator<'a> {
     state_variables: Vec<BddVariable>,
 }
 
+/// Lazily iterates over the witness networks (see `SymbolicAsyncGraph::pick_witness`) of all
+/// colors in a `GraphColors` set.
+pub struct WitnessIterator<'a> {
+    graph: &'a SymbolicAsyncGraph,
+    remaining: GraphColors,
+}
+
+/// The instantiated uninterpreted functions of a single color.
+///
+/// Explicit parameters can be used with different arguments in different update functions,
+/// hence they are represented as an `InstantiatedFunction` over formal arguments (use
+/// `InstantiatedFunction::to_fn_update` to apply them to network variables).
+/// The arguments of an implicit update function are the regulators of its variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColorModel {
+    explicit: Vec<InstantiatedFunction>,
+    implicit: Vec<Option<FnUpdate>>,
+}
+
+/// A concrete instantiation of an uninterpreted function (explicit parameter or implicit
+/// update function), i.e. an output value for every row of its `FunctionTable`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct InstantiatedFunction {
+    rows: Vec<(Vec<bool>, bool)>,
+}
+
 /// A symbolic encoding of asynchronous transition system of a `BooleanNetwork`.
 ///
 /// Provides standard pre/post operations for exploring the graph symbolically.
@@ -109,6 +157,56 @@ pub struct SymbolicAsyncGraph {
     unit_bdd: Bdd,
     // For every update function, store !v <=> function (used for pre/post)
     update_functions: Vec<Bdd>,
+    // For every variable, the BDD variable which disables its update function (if any).
+    perturbations: Vec<Option<BddVariable>>,
+}
+
+/// A symbolic encoding of the transition system of a `BooleanNetwork` under a configurable
+/// `UpdateScheme` (synchronous by default, hence the name).
+///
+/// Compared to `SymbolicAsyncGraph`, the symbolic context of this graph contains one extra
+/// BDD variable for every state variable, which is used to represent the successor state.
+/// Consequently, sets from a `SymbolicSyncGraph` cannot be mixed with sets from
+/// a `SymbolicAsyncGraph` of the same network.
+pub struct SymbolicSyncGraph {
+    // Asynchronous graph over the same symbolic context, which provides the state space.
+    graph: SymbolicAsyncGraph,
+    update_scheme: UpdateScheme,
+    // The transition relation of the scheme as a disjunction of partitioned relations.
+    update_choices: Vec<UpdateChoice>,
+}
+
+/// Describes which variables of a `BooleanNetwork` can be updated together in one step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateScheme {
+    /// Exactly one variable changes its value in every step.
+    Asynchronous,
+    /// All variables are updated simultaneously in every step.
+    Synchronous,
+    /// Any non-empty subset of variables is updated in one step (only steps which
+    /// actually change the state are considered).
+    GeneralizedAsynchronous,
+    /// Variables are divided into classes with decreasing priority. In every step, only
+    /// the first class in which some variable can change its value is updated (either
+    /// synchronously or asynchronously, depending on the class). Every variable of the
+    /// network must appear in exactly one class.
+    Priority(Vec<PriorityClass>),
+}
+
+/// One class of variables in the `UpdateScheme::Priority` scheme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriorityClass {
+    pub variables: Vec<VariableId>,
+    /// If true, all variables of the class are updated simultaneously. Otherwise, only
+    /// one of them changes its value in each step.
+    pub synchronous: bool,
+}
+
+/// **(internal)** One disjunct of a transition relation: a step from a state satisfying the
+/// `guard` to a successor state which satisfies all the (per-variable) `relations`.
+struct UpdateChoice {
+    guard: Bdd,
+    relations: Vec<Bdd>,
 }
 
 /// Symbolic context manages the mapping between entities of the Boolean network
@@ -122,11 +220,27 @@ pub struct SymbolicAsyncGraph {
 pub struct SymbolicContext {
     bdd: BddVariableSet,
     state_variables: Vec<BddVariable>,
+    // For every state variable, the extra BDD variables which directly follow it.
+    extra_state_variables: Vec<Vec<BddVariable>>,
+    // Named extra BDD variables which are not associated with any network variable.
+    extra_variables: Vec<(String, BddVariable)>,
     parameter_variables: Vec<BddVariable>,
     explicit_function_tables: Vec<FunctionTable>,
     implicit_function_tables: Vec<Option<FunctionTable>>,
 }
 
+/// Position of a named extra BDD variable (see `SymbolicContext::with_extra_variables`)
+/// in the variable ordering of a `SymbolicContext`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ExtraVariablePosition {
+    /// Before all other BDD variables.
+    First,
+    /// Directly after the given state variable (and its extra state variables).
+    AfterStateVariable(VariableId),
+    /// After all other BDD variables.
+    Last,
+}
+
 /// Function table maps one the table of an uninterpreted function to corresponding `Bdd` variables.
 ///
 /// The main functionality of a `FunctionTable` is that it provides an iterator over
@@ -0,0 +1,487 @@
+//! Symbolic computation of trap spaces of parametrised Boolean networks.
+//!
+//! A subspace assigns every variable either a fixed value or leaves it free (`*`). A subspace
+//! is a trap space (for some color) if no asynchronous transition leaves it, i.e. for every
+//! fixed variable, the update function evaluates to the fixed value in all states of
+//! the subspace.
+//!
+//! Subspaces are represented using a three-valued encoding: every network variable `x` has
+//! two BDD variables, "`x` can be true" and "`x` can be false" (a free variable can be both,
+//! at least one must hold). These are the first two extra state variables of
+//! a `SymbolicContext`, while the state variables themselves are used to quantify over
+//! the states of a subspace. Consequently, all colors of the network are handled at once.
+//!
+//! Minimal and maximal trap spaces are then obtained by symbolically comparing the computed
+//! trap spaces using the natural subspace ordering.
+
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::_impl_regulation_constraint::apply_regulation_constraints;
+use crate::symbolic_async_graph::{GraphColors, SymbolicContext};
+use crate::{BooleanNetwork, VariableId};
+use biodivine_lib_bdd::{Bdd, BddVariable};
+use std::convert::TryFrom;
+
+/// A symbolic context for working with sets of subspaces of a `BooleanNetwork`.
+pub struct SymbolicSpaceContext {
+    network: BooleanNetwork,
+    context: SymbolicContext,
+    // All valid subspaces in all valid colors.
+    unit_bdd: Bdd,
+}
+
+/// A symbolic set of pairs of subspaces and colors of a `SymbolicSpaceContext`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ColoredSpaces {
+    bdd: Bdd,
+    // For every network variable, the "can be true" and "can be false" BDD variables.
+    space_variables: Vec<(BddVariable, BddVariable)>,
+    state_variables: Vec<BddVariable>,
+    parameter_variables: Vec<BddVariable>,
+    // Used to create color sets compatible with the space context.
+    empty_colors: GraphColors,
+}
+
+impl SymbolicSpaceContext {
+    /// Create a new space context for the given `network`.
+    pub fn new(network: BooleanNetwork) -> Result<SymbolicSpaceContext, String> {
+        let context = SymbolicContext::with_extra_state_variables(&network, 2)?;
+        let unit_colors =
+            apply_regulation_constraints(context.mk_constant(true), &network, &context)
+                .map_err(|error| error.to_string())?;
+        let unit_bdd = network.variables().fold(unit_colors, |result, variable| {
+            let can_be_true = context.mk_extra_state_variable_is_true(variable, 0);
+            let can_be_false = context.mk_extra_state_variable_is_true(variable, 1);
+            result.and(&can_be_true.or(&can_be_false))
+        });
+        Ok(SymbolicSpaceContext {
+            network,
+            context,
+            unit_bdd,
+        })
+    }
+
+    /// Return a reference to the original Boolean network.
+    pub fn as_network(&self) -> &BooleanNetwork {
+        &self.network
+    }
+
+    /// Return a reference to the underlying symbolic context.
+    pub fn symbolic_context(&self) -> &SymbolicContext {
+        &self.context
+    }
+
+    /// A `Bdd` which is true for subspaces in which `variable` can be `true`.
+    pub fn mk_can_be_true(&self, variable: VariableId) -> Bdd {
+        self.context.mk_extra_state_variable_is_true(variable, 0)
+    }
+
+    /// A `Bdd` which is true for subspaces in which `variable` can be `false`.
+    pub fn mk_can_be_false(&self, variable: VariableId) -> Bdd {
+        self.context.mk_extra_state_variable_is_true(variable, 1)
+    }
+
+    /// Make a new empty set of colored subspaces.
+    pub fn mk_empty_spaces(&self) -> ColoredSpaces {
+        self.mk_spaces(self.context.mk_constant(false))
+    }
+
+    /// Make a set of all subspaces in all colors.
+    pub fn mk_unit_spaces(&self) -> ColoredSpaces {
+        self.mk_spaces(self.unit_bdd.clone())
+    }
+
+    /// Make a set containing the given `space` in all colors. A variable with `None` value
+    /// is free in the subspace.
+    pub fn mk_space(&self, space: &[Option<bool>]) -> ColoredSpaces {
+        let valuation: Vec<(BddVariable, bool)> = self
+            .network
+            .variables()
+            .zip(space.iter())
+            .flat_map(|(variable, value)| {
+                let extra = self.context.extra_state_variables(variable);
+                vec![
+                    (extra[0], *value != Some(false)),
+                    (extra[1], *value != Some(true)),
+                ]
+            })
+            .collect();
+        self.mk_spaces(self.unit_bdd.select(&valuation))
+    }
+
+    /// **(internal)** Wrap a `bdd` into a `ColoredSpaces` of this context.
+    fn mk_spaces(&self, bdd: Bdd) -> ColoredSpaces {
+        let space_variables = self
+            .network
+            .variables()
+            .map(|variable| {
+                let extra = self.context.extra_state_variables(variable);
+                (extra[0], extra[1])
+            })
+            .collect();
+        ColoredSpaces {
+            bdd,
+            space_variables,
+            state_variables: self.context.state_variables().clone(),
+            parameter_variables: self.context.parameter_variables().clone(),
+            empty_colors: GraphColors::new(self.context.mk_constant(false), &self.context),
+        }
+    }
+
+    /// **(internal)** A `Bdd` of states and subspaces such that the state
+    /// belongs to the subspace (only considering the given `variables`).
+    fn mk_state_in_space(&self, variables: &[VariableId]) -> Bdd {
+        variables
+            .iter()
+            .fold(self.context.mk_constant(true), |result, variable| {
+                let state_is_true = self.context.mk_state_variable_is_true(*variable);
+                let allowed = state_is_true
+                    .and(&self.mk_can_be_true(*variable))
+                    .or(&state_is_true.not().and(&self.mk_can_be_false(*variable)));
+                result.and(&allowed)
+            })
+    }
+
+    /// **(internal)** Extend every subspace of `spaces` such that `variable` becomes free.
+    fn widen(&self, spaces: &Bdd, variable: VariableId) -> Bdd {
+        let (t, f) = (
+            self.mk_can_be_true(variable),
+            self.mk_can_be_false(variable),
+        );
+        let extra = self.context.extra_state_variables(variable);
+        let fixed = spaces.and(&t.xor(&f));
+        fixed.project(&extra[0..2]).and(&t).and(&f)
+    }
+
+    /// **(internal)** Restrict every subspace of `spaces` in which `variable` is free
+    /// to both possible values of `variable`.
+    fn narrow(&self, spaces: &Bdd, variable: VariableId) -> Bdd {
+        let (t, f) = (
+            self.mk_can_be_true(variable),
+            self.mk_can_be_false(variable),
+        );
+        let extra = self.context.extra_state_variables(variable);
+        let free = spaces.and(&t).and(&f);
+        free.project(&extra[0..2]).and(&t.xor(&f))
+    }
+
+    /// **(internal)** All subspaces which contain some subspace of `spaces` (with the same color).
+    fn supersets(&self, spaces: &Bdd) -> Bdd {
+        self.network
+            .variables()
+            .fold(spaces.clone(), |result, variable| {
+                result.or(&self.widen(&result, variable))
+            })
+    }
+
+    /// **(internal)** All subspaces which are contained in some subspace of `spaces`
+    /// (with the same color).
+    fn subsets(&self, spaces: &Bdd) -> Bdd {
+        self.network
+            .variables()
+            .fold(spaces.clone(), |result, variable| {
+                result.or(&self.narrow(&result, variable))
+            })
+    }
+}
+
+impl ColoredSpaces {
+    /// View this set as a raw `Bdd`.
+    pub fn as_bdd(&self) -> &Bdd {
+        &self.bdd
+    }
+
+    /// Convert this set to a raw `Bdd`.
+    pub fn into_bdd(self) -> Bdd {
+        self.bdd
+    }
+
+    /// Make a copy of this set with a new `bdd` inheriting the original context.
+    pub fn copy(&self, bdd: Bdd) -> ColoredSpaces {
+        ColoredSpaces {
+            bdd,
+            space_variables: self.space_variables.clone(),
+            state_variables: self.state_variables.clone(),
+            parameter_variables: self.parameter_variables.clone(),
+            empty_colors: self.empty_colors.clone(),
+        }
+    }
+
+    /// Approximate number of (subspace, color) pairs in this set.
+    pub fn approx_cardinality(&self) -> f64 {
+        let used = 2 * self.space_variables.len() + self.parameter_variables.len();
+        let unused = self.bdd.num_vars() - u16::try_from(used).unwrap();
+        self.bdd.cardinality() / (2.0f64).powi(unused.into())
+    }
+
+    /// Colors for which this set contains at least one subspace.
+    pub fn colors(&self) -> GraphColors {
+        let mut variables = self.state_variables.clone();
+        for (t, f) in &self.space_variables {
+            variables.push(*t);
+            variables.push(*f);
+        }
+        self.empty_colors.copy(self.bdd.project(&variables))
+    }
+
+    /// Retain only the subspaces of this set which have one of the given `colors`.
+    pub fn intersect_colors(&self, colors: &GraphColors) -> ColoredSpaces {
+        self.copy(self.bdd.and(colors.as_bdd()))
+    }
+
+    /// Remove all subspaces with the given `colors`.
+    pub fn minus_colors(&self, colors: &GraphColors) -> ColoredSpaces {
+        self.copy(self.bdd.and_not(colors.as_bdd()))
+    }
+
+    /// List all subspaces in this set (each subspace is present for at least one color).
+    /// A variable with `None` value is free in the subspace.
+    pub fn spaces(&self) -> Vec<Vec<Option<bool>>> {
+        let mut variables = self.state_variables.clone();
+        variables.extend(self.parameter_variables.iter().cloned());
+        let mut remaining = self.bdd.project(&variables);
+        let mut result = Vec::new();
+        while let Some(witness) = remaining.sat_witness() {
+            let mut valuation = Vec::new();
+            let space = self
+                .space_variables
+                .iter()
+                .map(|(t, f)| {
+                    valuation.push((*t, witness[*t]));
+                    valuation.push((*f, witness[*f]));
+                    match (witness[*t], witness[*f]) {
+                        (true, false) => Some(true),
+                        (false, true) => Some(false),
+                        _ => None,
+                    }
+                })
+                .collect();
+            remaining = remaining.and_not(&remaining.select(&valuation));
+            result.push(space);
+        }
+        result
+    }
+}
+
+/// Set operations.
+impl Set for ColoredSpaces {
+    fn union(&self, other: &Self) -> Self {
+        self.copy(self.bdd.or(&other.bdd))
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        self.copy(self.bdd.and(&other.bdd))
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self.copy(self.bdd.and_not(&other.bdd))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bdd.is_false()
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        self.bdd.and_not(&other.bdd).is_false()
+    }
+}
+
+/// Compute all colored trap spaces of the network in the given `context`.
+pub fn trap_spaces(context: &SymbolicSpaceContext) -> ColoredSpaces {
+    let symbolic_context = &context.context;
+    let network = &context.network;
+    let mut result = context.unit_bdd.clone();
+    for variable in network.variables() {
+        let regulators = network.regulators(variable);
+        let function_is_true = match network.get_update_function(variable) {
+            Some(function) => symbolic_context.mk_fn_update_true(function),
+            None => symbolic_context.mk_implicit_function_is_true(variable, &regulators),
+        };
+        // Only the regulators can influence the update function, so it is sufficient
+        // to quantify over their states.
+        let in_space = context.mk_state_in_space(&regulators);
+        let regulator_states: Vec<BddVariable> = regulators
+            .iter()
+            .map(|it| symbolic_context.state_variables()[it.0])
+            .collect();
+        let can_become_true = in_space.and(&function_is_true).project(&regulator_states);
+        let can_become_false = in_space
+            .and(&function_is_true.not())
+            .project(&regulator_states);
+        // If the variable is fixed, the function cannot change it.
+        let (t, f) = (
+            context.mk_can_be_true(variable),
+            context.mk_can_be_false(variable),
+        );
+        let is_trap = f
+            .or(&can_become_false.not())
+            .and(&t.or(&can_become_true.not()));
+        result = result.and(&is_trap);
+    }
+    context.mk_spaces(result)
+}
+
+/// Compute the colored trap spaces of the network in the given `context` which do not
+/// contain any smaller trap space (for the same color).
+pub fn minimal_trap_spaces(context: &SymbolicSpaceContext) -> ColoredSpaces {
+    let traps = trap_spaces(context);
+    // Every strict superset of a trap space is obtained by widening at least one variable.
+    let widened = context
+        .network
+        .variables()
+        .fold(context.context.mk_constant(false), |result, variable| {
+            result.or(&context.widen(traps.as_bdd(), variable))
+        });
+    let non_minimal = context.supersets(&widened);
+    traps.copy(traps.as_bdd().and_not(&non_minimal))
+}
+
+/// Compute the colored trap spaces of the network in the given `context` which are not
+/// contained in any larger trap space (for the same color).
+///
+/// The whole state space (which is trivially a trap space) is not considered here,
+/// i.e. the result contains trap spaces which are maximal among the remaining
+/// trap spaces. If the whole space is the only trap space of some color, the color
+/// has no maximal trap space.
+pub fn maximal_trap_spaces(context: &SymbolicSpaceContext) -> ColoredSpaces {
+    let whole_space = vec![None; context.network.num_vars()];
+    let traps = trap_spaces(context).minus(&context.mk_space(&whole_space));
+    // Every strict subset of a trap space is obtained by narrowing at least one variable.
+    let narrowed = context
+        .network
+        .variables()
+        .fold(context.context.mk_constant(false), |result, variable| {
+            result.or(&context.narrow(traps.as_bdd(), variable))
+        });
+    let non_maximal = context.subsets(&narrowed);
+    traps.copy(traps.as_bdd().and_not(&non_maximal))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::{GraphColors, SymbolicAsyncGraph};
+    use crate::trap_spaces::{
+        maximal_trap_spaces, minimal_trap_spaces, trap_spaces, SymbolicSpaceContext,
+    };
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    /// All subspaces of a network with three variables.
+    fn all_spaces() -> Vec<Vec<Option<bool>>> {
+        let values = [Some(true), Some(false), None];
+        let mut result = Vec::new();
+        for a in &values {
+            for b in &values {
+                for c in &values {
+                    result.push(vec![*a, *b, *c]);
+                }
+            }
+        }
+        result
+    }
+
+    fn is_subspace(smaller: &[Option<bool>], larger: &[Option<bool>]) -> bool {
+        smaller
+            .iter()
+            .zip(larger.iter())
+            .all(|(s, l)| l.is_none() || s == l)
+    }
+
+    #[test]
+    fn trap_spaces_agree_with_async_graph() {
+        // The trap spaces depend on the implicit functions of `a` and `c`.
+        let bn =
+            BooleanNetwork::try_from("a -| b \n b -?? a \n a -> c \n c -?? c \n $b: !a").unwrap();
+        let stg = SymbolicAsyncGraph::new(bn.clone()).unwrap();
+        let context = SymbolicSpaceContext::new(bn).unwrap();
+        let traps = trap_spaces(&context);
+        let minimal = minimal_trap_spaces(&context);
+        let maximal = maximal_trap_spaces(&context);
+
+        // Colors in which the space is a trap space, computed explicitly using the async graph.
+        let spaces = all_spaces();
+        let trap_colors: Vec<GraphColors> = spaces
+            .iter()
+            .map(|space| {
+                let mut set = stg.mk_unit_colored_vertices();
+                for (variable, value) in stg.as_network().variables().zip(space.iter()) {
+                    if let Some(value) = value {
+                        set = set.intersect(&stg.fix_network_variable(variable, *value));
+                    }
+                }
+                let escaping = stg.post(&set).minus(&set).colors();
+                stg.mk_unit_colors().minus(&escaping)
+            })
+            .collect();
+
+        for (i, space) in spaces.iter().enumerate() {
+            let symbolic = context.mk_space(space);
+            let count = |set: &GraphColors| set.approx_cardinality();
+            assert_eq!(
+                count(&trap_colors[i]),
+                count(&traps.intersect(&symbolic).colors())
+            );
+
+            let mut expected_minimal = trap_colors[i].clone();
+            let mut expected_maximal = trap_colors[i].clone();
+            for (j, other) in spaces.iter().enumerate() {
+                if i != j && is_subspace(other, space) {
+                    expected_minimal = expected_minimal.minus(&trap_colors[j]);
+                }
+                if i != j && is_subspace(space, other) && other.iter().any(|it| it.is_some()) {
+                    expected_maximal = expected_maximal.minus(&trap_colors[j]);
+                }
+            }
+            if space.iter().all(|it| it.is_none()) {
+                expected_maximal = stg.mk_empty_colors();
+            }
+            assert_eq!(
+                count(&expected_minimal),
+                count(&minimal.intersect(&symbolic).colors())
+            );
+            assert_eq!(
+                count(&expected_maximal),
+                count(&maximal.intersect(&symbolic).colors())
+            );
+        }
+    }
+
+    #[test]
+    fn trap_spaces_of_toggle_switch() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            a -> c
+            b -> c
+            $a: !b
+            $b: !a
+            $c: a & b
+        ",
+        )
+        .unwrap();
+        let context = SymbolicSpaceContext::new(bn).unwrap();
+        let mut minimal = minimal_trap_spaces(&context).spaces();
+        minimal.sort();
+        let expected = vec![
+            vec![Some(false), Some(true), Some(false)],
+            vec![Some(true), Some(false), Some(false)],
+        ];
+        assert_eq!(expected, minimal);
+        assert_eq!(2.0, minimal_trap_spaces(&context).approx_cardinality());
+
+        // Variable c is free in the maximal trap spaces (c = 0 is not a trap space, because
+        // c can become true when a = b = 1).
+        let mut maximal = maximal_trap_spaces(&context).spaces();
+        maximal.sort();
+        let expected = vec![
+            vec![Some(false), Some(true), None],
+            vec![Some(true), Some(false), None],
+        ];
+        assert_eq!(expected, maximal);
+        assert!(maximal_trap_spaces(&context).is_subset(&trap_spaces(&context)));
+        assert!(!trap_spaces(&context).is_subset(&maximal_trap_spaces(&context)));
+        assert!(context.mk_empty_spaces().is_empty());
+        assert!(trap_spaces(&context).is_subset(&context.mk_unit_spaces()));
+    }
+}
@@ -36,8 +36,8 @@
 //!
 //! Note that in practice, it would be much better to use something like *saturation*, where
 //! the transitions which are applied are selected greedily one at a time, instead of applying
-//! them all at once using full `post` or `pre`. However, for the purpose of this tutorial, this
-//! should be sufficient.
+//! them all at once using full `post` or `pre` (this is implemented in the `reachability` module).
+//! However, for the purpose of this tutorial, this should be sufficient.
 //!
 //! Now we can observe that an SCC of vertex `v` is always the intersection of forward and backward
 //! reachable vertices from `v`. Hence we can write the following simple algorithm: